@@ -0,0 +1,259 @@
+use serde::{Deserialize, Serialize};
+
+use crate::DFInstance;
+use crate::data::memorylayout::OffsetSection;
+use crate::win::memory::memory::{mem_vec, read_mem};
+use crate::win::process::Process;
+
+// sub activity ids used in unit_activities.toml
+const PERFORMANCE_SUB_BASE: i32 = 100;
+const LEAD_DEMONSTRATION: i32 = 200;
+const WATCH_DEMONSTRATION: i32 = 201;
+
+/// An activity is a group of related events, e.g. a squad's training or a temple service
+#[derive(Default, Debug, Clone, Serialize, Deserialize)]
+pub struct Activity {
+    pub addr: usize,
+    pub activity_type: i16,
+    pub events: Vec<ActivityEvent>,
+}
+
+impl Activity {
+    pub unsafe fn new(df: &DFInstance, proc: &Process, addr: usize) -> Activity {
+        Activity {
+            addr,
+            activity_type: read_mem::<i16>(&proc.handle, addr + df.memory_layout.field_offset(OffsetSection::Activity, "activity_type")),
+            events: mem_vec::<usize>(&proc.handle, addr + df.memory_layout.field_offset(OffsetSection::Activity, "events"))
+                .iter()
+                .map(|&e| ActivityEvent::new(df, proc, e))
+                .collect(),
+        }
+    }
+}
+
+#[derive(Default, Debug, Clone, Serialize, Deserialize)]
+pub struct ActivityEvent {
+    pub addr: usize,
+    pub event_type: ActivityEventType,
+    pub name: String,
+    pub is_military: bool,
+    pub histfig_ids: Vec<i32>,
+    pub unit_ids: Vec<i32>,
+    pub detail: ActivityDetail,
+}
+
+/// The parts of an event that depend on its type
+#[derive(Default, Debug, Clone, Serialize, Deserialize)]
+pub enum ActivityDetail {
+    #[default]
+    None,
+    Drill {
+        leader_id: i32,
+        leader_name: String,
+        leader_translated_name: String,
+        skill_id: i16,
+        skill_name: String,
+        rounds: i32,
+    },
+    Prayer {
+        deity_id: i32,
+        deity_name: String,
+        deity_translated_name: String,
+        sphere_id: i32,
+        sphere_name: String,
+    },
+    Performance {
+        perf_type: i32,
+        performer_ids: Vec<i32>,
+    },
+    Research {
+        knowledge_category: i32,
+        knowledge_flag: i32,
+    },
+}
+
+impl ActivityEvent {
+    pub unsafe fn new(df: &DFInstance, proc: &Process, addr: usize) -> ActivityEvent {
+        // the event type is returned by the first vtable method
+        let vtable_addr = read_mem::<usize>(&proc.handle, addr);
+        let vtable = read_mem::<usize>(&proc.handle, vtable_addr);
+        let event_type = ActivityEventType::from(read_mem::<i32>(&proc.handle, vtable + 0x1));
+
+        // participants are a histfig vector followed by a unit vector
+        let participants_addr = addr + df.memory_layout.field_offset(OffsetSection::Activity, "participants");
+        let mut e = ActivityEvent {
+            addr,
+            event_type,
+            histfig_ids: mem_vec::<i32>(&proc.handle, participants_addr),
+            unit_ids: mem_vec::<i32>(&proc.handle, participants_addr + 3 * size_of::<usize>()),
+            ..Default::default()
+        };
+
+        e.detail = match event_type {
+            ActivityEventType::TrainingSession |
+            ActivityEventType::CombatTraining |
+            ActivityEventType::SkillDemonstration |
+            ActivityEventType::IndividualSkillDrill => {
+                let leader_id = read_mem::<i32>(&proc.handle, addr + df.memory_layout.field_offset(OffsetSection::Activity, "sq_lead"));
+                let skill_id = read_mem::<i16>(&proc.handle, addr + df.memory_layout.field_offset(OffsetSection::Activity, "sq_skill"));
+                let (leader_name, leader_translated_name) = df.get_histfig_names(proc, leader_id);
+                ActivityDetail::Drill {
+                    leader_id,
+                    leader_name,
+                    leader_translated_name,
+                    skill_id,
+                    skill_name: df.game_data.skills.get(skill_id as usize).map(|s| s.name.clone()).unwrap_or_default(),
+                    rounds: read_mem::<i32>(&proc.handle, addr + df.memory_layout.field_offset(OffsetSection::Activity, "sq_train_rounds")),
+                }
+            },
+            ActivityEventType::Prayer | ActivityEventType::Worship => {
+                let deity_id = read_mem::<i32>(&proc.handle, addr + df.memory_layout.field_offset(OffsetSection::Activity, "pray_deity"));
+                let sphere_id = read_mem::<i32>(&proc.handle, addr + df.memory_layout.field_offset(OffsetSection::Activity, "pray_sphere"));
+                let (deity_name, deity_translated_name) = df.get_histfig_names(proc, deity_id);
+                ActivityDetail::Prayer {
+                    deity_id,
+                    deity_name,
+                    deity_translated_name,
+                    sphere_id,
+                    sphere_name: df.game_data.sphere_names.get(&sphere_id.to_string()).cloned().unwrap_or_default(),
+                }
+            },
+            ActivityEventType::Performance => {
+                let performer_ids = mem_vec::<usize>(&proc.handle, addr + df.memory_layout.field_offset(OffsetSection::Activity, "perf_participants"))
+                    .iter()
+                    .map(|&p| read_mem::<i32>(&proc.handle, p + df.memory_layout.field_offset(OffsetSection::Activity, "perf_histfig")))
+                    .collect();
+                ActivityDetail::Performance {
+                    perf_type: read_mem::<i32>(&proc.handle, addr + df.memory_layout.field_offset(OffsetSection::Activity, "perf_type")),
+                    performer_ids,
+                }
+            },
+            ActivityEventType::Research | ActivityEventType::PonderTopic |
+            ActivityEventType::DiscussTopic | ActivityEventType::TeachTopic => {
+                ActivityDetail::Research {
+                    knowledge_category: read_mem::<i32>(&proc.handle, addr + df.memory_layout.field_offset(OffsetSection::Activity, "knowledge_category")),
+                    knowledge_flag: read_mem::<i32>(&proc.handle, addr + df.memory_layout.field_offset(OffsetSection::Activity, "knowledge_flag")),
+                }
+            },
+            _ => ActivityDetail::None,
+        };
+
+        e.read_name(df, event_type as i32);
+        e
+    }
+
+    /// Looks up the name of an activity or sub activity in unit_activities
+    fn read_name(&mut self, df: &DFInstance, id: i32) {
+        for a in &df.game_data.unit_activities {
+            if let Some(sub) = a.sub.iter().find(|s| s.id == id) {
+                self.name = sub.name.clone();
+                self.is_military = a.is_military;
+                return;
+            }
+        }
+        if let Some(a) = df.game_data.unit_activities.iter().find(|a| a.sub.is_empty() && a.id == id) {
+            self.name = a.name.clone();
+            self.is_military = a.is_military;
+        }
+    }
+
+    pub fn has_participant(&self, histfig_id: i32, unit_id: i32) -> bool {
+        (histfig_id >= 0 && self.histfig_ids.contains(&histfig_id)) || self.unit_ids.contains(&unit_id)
+    }
+
+    /// What a single participant is doing in this event, e.g. "Play Music" rather than "Performance"
+    pub fn name_for(&self, df: &DFInstance, histfig_id: i32) -> String {
+        let sub_id = match &self.detail {
+            ActivityDetail::Performance { perf_type, performer_ids } => {
+                match performer_ids.contains(&histfig_id) {
+                    true => PERFORMANCE_SUB_BASE + perf_type,
+                    false => PERFORMANCE_SUB_BASE + 4, // audience
+                }
+            },
+            ActivityDetail::Drill { leader_id, .. } if self.event_type == ActivityEventType::SkillDemonstration => {
+                match *leader_id == histfig_id {
+                    true => LEAD_DEMONSTRATION,
+                    false => WATCH_DEMONSTRATION,
+                }
+            },
+            _ => return self.name.clone(),
+        };
+
+        let mut named = self.clone();
+        named.read_name(df, sub_id);
+        match &self.detail {
+            ActivityDetail::Drill { skill_name, .. } => named.name.replace("[skill]", skill_name),
+            _ => named.name,
+        }
+    }
+}
+
+#[derive(Default, Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum ActivityEventType {
+    #[default]
+    None = -1,
+    TrainingSession,
+    CombatTraining,
+    SkillDemonstration,
+    IndividualSkillDrill,
+    Sparring,
+    RangedPractice,
+    Harassment,
+    Conversation,
+    Conflict,
+    Guard,
+    Reunion,
+    Prayer,
+    Socialize,
+    Worship,
+    Performance,
+    Research,
+    PonderTopic,
+    DiscussTopic,
+    Read,
+    FillServiceOrder,
+    Write,
+    CopyWrittenContent,
+    TeachTopic,
+    Play,
+    MakeBelieve,
+    PlayWithToy,
+    Encounter,
+    StoreObject,
+}
+
+impl From<i32> for ActivityEventType {
+    fn from(value: i32) -> Self {
+        match value {
+            0 => ActivityEventType::TrainingSession,
+            1 => ActivityEventType::CombatTraining,
+            2 => ActivityEventType::SkillDemonstration,
+            3 => ActivityEventType::IndividualSkillDrill,
+            4 => ActivityEventType::Sparring,
+            5 => ActivityEventType::RangedPractice,
+            6 => ActivityEventType::Harassment,
+            7 => ActivityEventType::Conversation,
+            8 => ActivityEventType::Conflict,
+            9 => ActivityEventType::Guard,
+            10 => ActivityEventType::Reunion,
+            11 => ActivityEventType::Prayer,
+            12 => ActivityEventType::Socialize,
+            13 => ActivityEventType::Worship,
+            14 => ActivityEventType::Performance,
+            15 => ActivityEventType::Research,
+            16 => ActivityEventType::PonderTopic,
+            17 => ActivityEventType::DiscussTopic,
+            18 => ActivityEventType::Read,
+            19 => ActivityEventType::FillServiceOrder,
+            20 => ActivityEventType::Write,
+            21 => ActivityEventType::CopyWrittenContent,
+            22 => ActivityEventType::TeachTopic,
+            23 => ActivityEventType::Play,
+            24 => ActivityEventType::MakeBelieve,
+            25 => ActivityEventType::PlayWithToy,
+            26 => ActivityEventType::Encounter,
+            27 => ActivityEventType::StoreObject,
+            _ => ActivityEventType::None,
+        }
+    }
+}
@@ -1,10 +1,25 @@
 use std::sync::Arc;
 
-use axum::{extract::State, Json};
+use axum::{extract::{Query, State}, http::StatusCode, Json};
+use serde::Deserialize;
 use tokio::sync::Mutex;
 
-use crate::dwarf::dwarf::Dwarf;
+use crate::activity::Activity;
+use crate::artifact::Artifact;
+use crate::counters::{counter_alerts, CounterAlert};
+use crate::dwarf::dwarf::{Dwarf, UnitType};
 use crate::dfinstance::DFInstance;
+use crate::histevent::{EventQuery, HistEvent};
+use crate::histfigure::Veteran;
+use crate::knowledge::{fortress_knowledge, TopicSummary};
+use crate::location::{multiple_jobs, LocationStaffing, MultipleJobs};
+use crate::mood::MoodReport;
+use crate::relationship::{marriages, Marriage, RelationGraph};
+use crate::religion::{fortress_deities, DeitySummary};
+use crate::squad::{Squad, SoldierEquipment};
+use crate::stock::{stock_counts, StockCount, StockQuery};
+use crate::win::process::Process;
+use crate::PROCESS_NAME;
 
 #[derive(Clone)]
 pub struct AppState {
@@ -44,4 +59,156 @@ pub async fn get_gamedata_handler(State(state): State<AppState>) -> Json<serde_j
 pub async fn get_dwarves_handler(State(state): State<AppState>) -> Json<Vec<Dwarf>> {
     let df = state.df.lock().await;
     Json(df.dwarves.clone())
-}
\ No newline at end of file
+}
+
+#[derive(Deserialize)]
+pub struct UnitQuery {
+    pub unit_type: Option<UnitType>,
+}
+
+/// Every unit on the map or just one type of them, e.g. `/units?unit_type=Visitor`
+pub async fn get_units_handler(State(state): State<AppState>, Query(query): Query<UnitQuery>) -> Json<Vec<Dwarf>> {
+    let df = state.df.lock().await;
+    match query.unit_type {
+        Some(t) => Json(df.units_of_type(t).into_iter().cloned().collect()),
+        None => Json(df.units.clone()),
+    }
+}
+
+/// Dwarves who are starving, passing out or otherwise in trouble
+pub async fn get_alerts_handler(State(state): State<AppState>) -> Json<Vec<CounterAlert>> {
+    let df = state.df.lock().await;
+    Json(counter_alerts(&df.dwarves))
+}
+
+pub async fn get_squads_handler(State(state): State<AppState>) -> Json<Vec<Squad>> {
+    let df = state.df.lock().await;
+    Json(df.squads.clone())
+}
+
+/// Lists the soldiers whose gear doesn't match their squad's uniform
+pub async fn get_squad_equipment_handler(State(state): State<AppState>) -> Json<Vec<SoldierEquipment>> {
+    let df = state.df.lock().await;
+    Json(df.squads.iter().flat_map(|s| s.equipment_report(&df.dwarves)).collect())
+}
+
+#[derive(Deserialize)]
+pub struct SquadAssignment {
+    pub dwarf_id: i32,
+    pub squad_id: i32,
+    /// first free slot if not given
+    pub position: Option<i32>,
+}
+
+#[derive(Deserialize)]
+pub struct SquadRemoval {
+    pub dwarf_id: i32,
+}
+
+/// Writes a squad assignment to the game and returns the updated dwarf
+pub async fn assign_squad_handler(State(state): State<AppState>, Json(req): Json<SquadAssignment>) -> Result<Json<Dwarf>, (StatusCode, String)> {
+    let mut df = state.df.lock().await;
+    unsafe {
+        let proc = Process::new_by_name(PROCESS_NAME).map_err(|e| (StatusCode::SERVICE_UNAVAILABLE, e.to_string()))?;
+        df.assign_to_squad(&proc, req.dwarf_id, req.squad_id, req.position).map_err(|e| (StatusCode::BAD_REQUEST, e.to_string()))?;
+    }
+    let dwarf = df.dwarves.iter().find(|d| d.id == req.dwarf_id).cloned().unwrap_or_default();
+    Ok(Json(dwarf))
+}
+
+/// Removes a dwarf from their squad and returns the updated dwarf
+pub async fn remove_squad_handler(State(state): State<AppState>, Json(req): Json<SquadRemoval>) -> Result<Json<Dwarf>, (StatusCode, String)> {
+    let mut df = state.df.lock().await;
+    unsafe {
+        let proc = Process::new_by_name(PROCESS_NAME).map_err(|e| (StatusCode::SERVICE_UNAVAILABLE, e.to_string()))?;
+        df.remove_from_squad(&proc, req.dwarf_id).map_err(|e| (StatusCode::BAD_REQUEST, e.to_string()))?;
+    }
+    let dwarf = df.dwarves.iter().find(|d| d.id == req.dwarf_id).cloned().unwrap_or_default();
+    Ok(Json(dwarf))
+}
+
+pub async fn get_activities_handler(State(state): State<AppState>) -> Json<Vec<Activity>> {
+    let df = state.df.lock().await;
+    Json(df.activities.clone())
+}
+
+pub async fn get_veterans_handler(State(state): State<AppState>) -> Json<Vec<Veteran>> {
+    let df = state.df.lock().await;
+    Json(df.veterans())
+}
+
+/// Looks up historical events, e.g. `/events?histfig_id=12&from_year=100&event_type=HistFigureDied`.
+/// Only the returned page is described, use `offset` and `limit` for the rest.
+pub async fn get_events_handler(State(state): State<AppState>, Query(query): Query<EventQuery>) -> Result<Json<Vec<HistEvent>>, (StatusCode, String)> {
+    let df = state.df.lock().await;
+    let mut events = df.query_events(&query);
+    unsafe {
+        let proc = Process::new_by_name(PROCESS_NAME).map_err(|e| (StatusCode::SERVICE_UNAVAILABLE, e.to_string()))?;
+        for e in events.iter_mut() {
+            e.describe(&df, &proc);
+        }
+    }
+    Ok(Json(events))
+}
+
+pub async fn get_artifacts_handler(State(state): State<AppState>) -> Json<Vec<Artifact>> {
+    let df = state.df.lock().await;
+    Json(df.artifacts.clone())
+}
+
+/// Fortress stock grouped by type, material and quality, e.g. `/items?item_type=Weapon&min_quality=Superior`
+pub async fn get_items_handler(State(state): State<AppState>, Query(query): Query<StockQuery>) -> Json<Vec<StockCount>> {
+    let df = state.df.lock().await;
+    Json(stock_counts(&df.items, &query))
+}
+
+/// Dwarves in a strange mood and those who could still get one
+pub async fn get_moods_handler(State(state): State<AppState>) -> Json<MoodReport> {
+    let df = state.df.lock().await;
+    Json(MoodReport::new(&df, &df.dwarves))
+}
+
+/// Every topic known or being researched in the fortress and who by
+pub async fn get_knowledge_handler(State(state): State<AppState>) -> Json<Vec<TopicSummary>> {
+    let df = state.df.lock().await;
+    Json(fortress_knowledge(&df.dwarves))
+}
+
+#[derive(Deserialize)]
+pub struct DwarfQuery {
+    pub dwarf_id: i32,
+}
+
+/// Everyone one dwarf is related to or knows, e.g. `/relations?dwarf_id=1234`
+pub async fn get_relations_handler(State(state): State<AppState>, Query(query): Query<DwarfQuery>) -> Result<Json<RelationGraph>, (StatusCode, String)> {
+    let df = state.df.lock().await;
+    let d = df.dwarves.iter().find(|d| d.id == query.dwarf_id).ok_or((StatusCode::NOT_FOUND, format!("no dwarf with id {}", query.dwarf_id)))?;
+    Ok(Json(RelationGraph::for_dwarf(d, &df.dwarves)))
+}
+
+pub async fn get_family_tree_handler(State(state): State<AppState>) -> Json<RelationGraph> {
+    let df = state.df.lock().await;
+    Json(RelationGraph::family_tree(&df.dwarves))
+}
+
+pub async fn get_marriages_handler(State(state): State<AppState>) -> Json<Vec<Marriage>> {
+    let df = state.df.lock().await;
+    Json(marriages(&df.dwarves))
+}
+
+/// Every deity worshipped in the fortress and whether its worshippers need a temple
+pub async fn get_deities_handler(State(state): State<AppState>) -> Json<Vec<DeitySummary>> {
+    let df = state.df.lock().await;
+    Json(fortress_deities(&df.dwarves))
+}
+
+/// The fortress's locations with who works there and which jobs are empty
+pub async fn get_locations_handler(State(state): State<AppState>) -> Json<Vec<LocationStaffing>> {
+    let df = state.df.lock().await;
+    Json(df.locations.iter().map(|l| LocationStaffing::new(l, &df.units)).collect())
+}
+
+pub async fn get_multiple_jobs_handler(State(state): State<AppState>) -> Json<Vec<MultipleJobs>> {
+    let df = state.df.lock().await;
+    Json(multiple_jobs(&df.locations, &df.dwarves))
+}
@@ -0,0 +1,63 @@
+use serde::{Deserialize, Serialize};
+
+use crate::DFInstance;
+use crate::data::memorylayout::OffsetSection;
+use crate::items::{GeneralRefType, Item};
+use crate::win::memory::memory::read_mem;
+use crate::win::process::Process;
+
+#[derive(Default, Debug, Clone, Serialize, Deserialize)]
+pub struct Artifact {
+    pub id: i32,
+    pub addr: usize,
+    pub name: String,
+    pub translated_name: String,
+    pub item: Item,
+    /// e.g. "steel battle axe"
+    pub item_name: String,
+    pub creator_id: i32,
+    pub creator_name: String,
+    pub creator_translated_name: String,
+    /// the unit id of the creator if they are one of our dwarves, -1 otherwise
+    pub creator_dwarf_id: i32,
+    pub year_created: i32,
+    /// the unit carrying the artifact, -1 if nobody is
+    pub holder_id: i32,
+    pub holder_name: String,
+}
+
+impl Artifact {
+    pub unsafe fn new(df: &DFInstance, proc: &Process, addr: usize) -> Artifact {
+        let item_addr = read_mem::<usize>(&proc.handle, addr + df.memory_layout.field_offset(OffsetSection::Item, "artifact_item"));
+        let (name, translated_name) = df.read_names(proc, addr + df.memory_layout.field_offset(OffsetSection::Item, "artifact_name"));
+        let item = match item_addr {
+            0 => Item::default(),
+            a => Item::new(df, proc, a),
+        };
+
+        let mut a = Artifact {
+            id:        read_mem::<i32>(&proc.handle, addr + df.memory_layout.field_offset(OffsetSection::Item, "artifact_id")),
+            addr,
+            name,
+            translated_name,
+            item_name: item.display_name(),
+            holder_id: item.ref_id(GeneralRefType::UnitHolder),
+            creator_id: item.ref_id(GeneralRefType::HistoricalFigure),
+            creator_dwarf_id: -1,
+            item,
+            ..Default::default()
+        };
+
+        // the creation event has the year, and the creator too if the item has no figure ref
+        if let Some(&(creator_id, year)) = df.artifact_creators.get(&a.id) {
+            if a.creator_id < 0 {
+                a.creator_id = creator_id;
+            }
+            a.year_created = year;
+        }
+        if a.creator_id >= 0 {
+            (a.creator_name, a.creator_translated_name) = df.get_histfig_names(proc, a.creator_id);
+        }
+        a
+    }
+}
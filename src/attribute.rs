@@ -2,6 +2,16 @@ use std::fmt;
 
 use serde::{Deserialize, Serialize};
 
+use crate::caste::caste::Caste;
+use crate::data::gamedata::GameData;
+
+/// The cost to improve DF treats as normal
+const DEFAULT_CTI: i32 = 500;
+/// No attribute can go above this
+const ATTRIBUTE_LIMIT: i32 = 5000;
+/// The descriptor level with no text, an average value
+const AVERAGE_LEVEL: i32 = 5;
+
 #[derive(Default, Debug, PartialEq, Serialize, Deserialize, Clone)]
 pub struct Attribute {
     pub id: i32,
@@ -16,6 +26,107 @@ pub struct Attribute {
     pub cti: i32,
     pub descriptor: String,
     pub descriptor_index: i32,
+    /// syndromes changing this attribute
+    pub syndromes: Vec<String>,
+}
+
+impl Attribute {
+    /// Fills in the potential, ratings and descriptor from the caste's ranges and growth costs
+    pub fn calculate(&mut self, game_data: &GameData, caste: &Caste) {
+        let idx = self.id as usize;
+        self.cti = match caste.attribute_costs.get(idx) {
+            Some(&c) if c > 0 => c,
+            _ => DEFAULT_CTI,
+        };
+        // babies and animals don't always have a max, use the caste's cap instead
+        if self.max <= 0 {
+            self.max = caste.attribute_caps.get(idx).map_or(self.value, |&cap| self.value * cap / 100);
+        }
+        self.max = self.max.min(ATTRIBUTE_LIMIT).max(self.value);
+
+        self.calculate_potential();
+        if let Some(range) = caste.attribute_ranges.get(idx) {
+            self.rating = Self::range_rating(self.display_value, range);
+            self.rating_potential = Self::range_rating(self.value_potential, range);
+            self.descriptor_index = Self::descriptor_level(self.display_value, range);
+        }
+
+        self.descriptor = game_data.attributes.iter()
+            .find(|a| a.name == self.name)
+            .and_then(|a| a.levels.get(&self.descriptor_index.to_string()))
+            .cloned()
+            .unwrap_or_default();
+    }
+
+    /// Like Dwarf Therapist, a dwarf is expected to close half the gap to their max. The balanced
+    /// value gives more of that gap to attributes that are cheap to improve.
+    fn calculate_potential(&mut self) {
+        let gap = self.max - self.value;
+        self.value_potential = self.value + gap / 2;
+        self.value_balanced = (self.value + (gap / 2) * DEFAULT_CTI / self.cti).min(self.max);
+    }
+
+    /// Where a value falls in the caste's range, 0 at the minimum to 100 at the maximum
+    fn range_rating(value: i32, range: &[i32; 7]) -> i32 {
+        if value <= range[0] {
+            return 0;
+        }
+        for i in 1..range.len() {
+            if value <= range[i] {
+                let span = (range[i] - range[i - 1]).max(1) as f32;
+                let within = (value - range[i - 1]) as f32 / span;
+                return (((i - 1) as f32 + within) / 6.0 * 100.0).round() as i32;
+            }
+        }
+        100
+    }
+
+    /// The descriptor bands step away from the caste median by a quarter of the distance from median to max,
+    /// e.g. dwarves are strong from 1500 and weak at 1000 or below
+    fn descriptor_level(value: i32, range: &[i32; 7]) -> i32 {
+        let median = range[3];
+        let step = ((range[6] - median) / 4).max(1);
+        let steps = match value >= median {
+            true => (value - median) / step,
+            false => -((median - value) / step),
+        };
+        (AVERAGE_LEVEL + steps).clamp(1, 9)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// A dwarf's strength range
+    const STRENGTH: [i32; 7] = [450, 950, 1150, 1250, 1350, 1550, 2250];
+
+    #[test]
+    fn range_rating_spans_the_caste_range() {
+        assert_eq!(Attribute::range_rating(0, &STRENGTH), 0);
+        assert_eq!(Attribute::range_rating(450, &STRENGTH), 0);
+        assert_eq!(Attribute::range_rating(1250, &STRENGTH), 50);
+        assert_eq!(Attribute::range_rating(2250, &STRENGTH), 100);
+        assert_eq!(Attribute::range_rating(4000, &STRENGTH), 100);
+    }
+
+    #[test]
+    fn descriptor_level_steps_from_the_median() {
+        assert_eq!(Attribute::descriptor_level(1250, &STRENGTH), AVERAGE_LEVEL);
+        assert_eq!(Attribute::descriptor_level(1499, &STRENGTH), AVERAGE_LEVEL);
+        assert_eq!(Attribute::descriptor_level(1500, &STRENGTH), 6);
+        assert_eq!(Attribute::descriptor_level(1000, &STRENGTH), 4);
+        assert_eq!(Attribute::descriptor_level(5000, &STRENGTH), 9);
+        assert_eq!(Attribute::descriptor_level(0, &STRENGTH), 1);
+    }
+
+    #[test]
+    fn calculate_keeps_values_above_the_limit() {
+        let mut a = Attribute { value: 6000, max: 7000, ..Default::default() };
+        a.calculate(&GameData::default(), &Caste::default());
+        assert_eq!(a.max, 6000);
+        assert_eq!(a.value_potential, 6000);
+    }
 }
 
 #[derive(Debug, Default, PartialEq, Clone, Copy, Serialize, Deserialize, Eq, Hash)]
@@ -6,6 +6,10 @@ pub mod caste {
     use crate::win::process::Process;
     use crate::{flagarray::FlagArray, util::memory::read_mem_as_string, DFInstance};
 
+    /// 6 physical and 13 mental attributes
+    const ATTRIBUTE_COUNT: usize = 19;
+    /// DF's personality facets, the game data's pseudo-facets with negative ids aren't stored with them
+    const FACET_COUNT: usize = 50;
 
     #[derive(Default, Debug, Clone, Serialize, Deserialize)]
     pub struct Caste {
@@ -19,6 +23,16 @@ pub mod caste {
         pub adult_size: i32,
         pub body_parts_addr: Vec<usize>,
         pub flags: FlagArray,
+        /// the seven raw range values for every attribute, indexed by attribute id
+        pub attribute_ranges: Vec<[i32; 7]>,
+        /// how much experience each attribute needs to improve
+        pub attribute_costs: Vec<i32>,
+        /// the percentage of the starting value each attribute can grow to
+        pub attribute_caps: Vec<i32>,
+        /// the percentage of normal experience gained for every skill, indexed by skill id
+        pub skill_rates: Vec<i32>,
+        /// min, median and max of every personality facet, indexed by facet id
+        pub trait_ranges: Vec<[i16; 3]>,
     }
 
     impl Caste {
@@ -35,9 +49,75 @@ pub mod caste {
             };
 
             c.check_flags(proc, df);
+            c.read_attribute_info(proc, df);
+            c.read_skill_rates(proc, df);
+            c.read_trait_ranges(proc, df);
             c
         }
 
+        /// The first of the four skill rate arrays holds the learning rates
+        unsafe fn read_skill_rates(&mut self, proc: &Process, df: &DFInstance) {
+            let addr = self.address + df.memory_layout.field_offset(OffsetSection::Caste, "skill_rates");
+            self.skill_rates = (0..df.game_data.skills.len())
+                .map(|id| read_mem::<i32>(&proc.handle, addr + id * size_of::<i32>()))
+                .collect();
+        }
+
+        /// Facet ranges are stored as three arrays of min, median and max values
+        unsafe fn read_trait_ranges(&mut self, proc: &Process, df: &DFInstance) {
+            let addr = self.address + df.memory_layout.field_offset(OffsetSection::Caste, "caste_trait_ranges");
+            self.trait_ranges = (0..FACET_COUNT)
+                .map(|id| {
+                    let mut range = [0; 3];
+                    for (i, r) in range.iter_mut().enumerate() {
+                        *r = read_mem::<u16>(&proc.handle, addr + (i * FACET_COUNT + id) * size_of::<u16>()) as i16;
+                    }
+                    range
+                })
+                .collect();
+        }
+
+        /// How fast this caste learns a skill, 100 is normal
+        pub fn skill_rate(&self, skill_id: i32) -> i32 {
+            self.skill_rates.get(skill_id as usize).copied().unwrap_or(100)
+        }
+
+        /// How far a facet value is from the caste's median, from -100 at the caste minimum to 100 at the maximum.
+        /// Pseudo-facets have no range and return `None`.
+        pub fn trait_extremeness(&self, facet_id: i32, value: i16) -> Option<i32> {
+            if facet_id < 0 || facet_id as usize >= FACET_COUNT {
+                return None;
+            }
+            let [min, median, max] = match self.trait_ranges.get(facet_id as usize) {
+                Some(&r) => r.map(|v| v as i32),
+                None => [0, 50, 100],
+            };
+            let value = value as i32;
+            let extremeness = match value >= median {
+                true => (value - median) * 100 / (max - median).max(1),
+                false => -((median - value) * 100 / (median - min).max(1)),
+            };
+            Some(extremeness.clamp(-100, 100))
+        }
+
+        /// Reads the attribute ranges, growth costs and caps. Mental attributes follow the six physical ones.
+        unsafe fn read_attribute_info(&mut self, proc: &Process, df: &DFInstance) {
+            let ranges_addr = self.address + df.memory_layout.field_offset(OffsetSection::Caste, "caste_phys_att_ranges");
+            let rates_addr = self.address + df.memory_layout.field_offset(OffsetSection::Caste, "caste_att_rates");
+            let caps_addr = self.address + df.memory_layout.field_offset(OffsetSection::Caste, "caste_att_caps");
+
+            for id in 0..ATTRIBUTE_COUNT {
+                let mut range = [0; 7];
+                for (i, r) in range.iter_mut().enumerate() {
+                    *r = read_mem::<i32>(&proc.handle, ranges_addr + (id * 7 + i) * size_of::<i32>());
+                }
+                self.attribute_ranges.push(range);
+                // each rate is improvement cost, decay, demotion and an unused value
+                self.attribute_costs.push(read_mem::<i32>(&proc.handle, rates_addr + id * 4 * size_of::<i32>()));
+                self.attribute_caps.push(read_mem::<i32>(&proc.handle, caps_addr + id * size_of::<i32>()));
+            }
+        }
+
         pub unsafe fn check_flags(&mut self, proc: &Process, df: &DFInstance) {
             if self.flags.flags.get(97).unwrap_or_default() {
                 self.baby_age = match read_mem::<i32>(&proc.handle, self.address + df.memory_layout.field_offset(OffsetSection::Caste, "baby_age")) {
@@ -85,5 +165,48 @@ pub mod caste {
                 let _ = self.flags.flags.set(201, true);
             }
         }
+
+        /// The singular name of a body part, e.g. "left upper arm"
+        pub unsafe fn body_part_name(&self, df: &DFInstance, proc: &Process, id: i16) -> String {
+            let bp_addr = match self.body_parts_addr.get(id as usize) {
+                Some(&a) if id >= 0 => a,
+                _ => return String::new(),
+            };
+            match mem_vec::<usize>(&proc.handle, bp_addr + df.memory_layout.field_offset(OffsetSection::Health, "names_vector")).first() {
+                Some(&name) => read_mem_as_string(proc, name),
+                None => String::new(),
+            }
+        }
+    }
+
+    #[cfg(test)]
+    mod tests {
+        use super::*;
+
+        #[test]
+        fn trait_extremeness_scales_to_the_caste_range() {
+            let caste = Caste { trait_ranges: vec![[0, 50, 100], [20, 40, 90]], ..Default::default() };
+            assert_eq!(caste.trait_extremeness(0, 50), Some(0));
+            assert_eq!(caste.trait_extremeness(0, 0), Some(-100));
+            assert_eq!(caste.trait_extremeness(0, 75), Some(50));
+            assert_eq!(caste.trait_extremeness(1, 30), Some(-50));
+            assert_eq!(caste.trait_extremeness(1, 65), Some(50));
+            assert_eq!(caste.trait_extremeness(1, 100), Some(100));
+        }
+
+        #[test]
+        fn trait_extremeness_defaults_without_a_range() {
+            let caste = Caste::default();
+            assert_eq!(caste.trait_extremeness(10, 25), Some(-50));
+            assert_eq!(caste.trait_extremeness(49, 100), Some(100));
+        }
+
+        #[test]
+        fn pseudo_facets_have_no_extremeness() {
+            let caste = Caste::default();
+            assert_eq!(caste.trait_extremeness(-1, 60), None);
+            assert_eq!(caste.trait_extremeness(-2, 60), None);
+            assert_eq!(caste.trait_extremeness(FACET_COUNT as i32, 60), None);
+        }
     }
-}
\ No newline at end of file
+}
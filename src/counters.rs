@@ -0,0 +1,212 @@
+use std::cmp::Reverse;
+
+use serde::{Deserialize, Serialize};
+
+use crate::DFInstance;
+use crate::data::memorylayout::OffsetSection;
+use crate::dwarf::dwarf::Dwarf;
+use crate::win::memory::memory::read_mem;
+use crate::win::process::Process;
+
+// hunger, thirst and sleep timers count up until the unit eats, drinks or sleeps
+const HUNGRY: i32 = 50000;
+const STARVING: i32 = 75000;
+const THIRSTY: i32 = 25000;
+const DEHYDRATED: i32 = 50000;
+const DROWSY: i32 = 57600;
+const VERY_DROWSY: i32 = 150000;
+const TIRED: i32 = 2000;
+const OVER_EXERTED: i32 = 6000;
+const SEVERE_PAIN: i32 = 100;
+/// Blood levels below these percentages of the max
+const BLOOD_LOSS: i32 = 75;
+const SEVERE_BLOOD_LOSS: i32 = 50;
+
+#[derive(Default, Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+pub enum CounterLevel {
+    #[default]
+    Normal,
+    Warning,
+    Critical,
+}
+
+/// One of a unit's counters with a label for how bad it is
+#[derive(Default, Debug, Clone, Serialize, Deserialize)]
+pub struct Counter {
+    pub value: i32,
+    pub level: CounterLevel,
+    /// e.g. "Starving", empty when normal
+    pub status: String,
+}
+
+impl Counter {
+    /// A counter that gets worse as it goes up
+    fn rising(value: i32, warning: (i32, &str), critical: (i32, &str)) -> Counter {
+        let (level, status) = match value {
+            v if v >= critical.0 => (CounterLevel::Critical, critical.1),
+            v if v >= warning.0 => (CounterLevel::Warning, warning.1),
+            _ => (CounterLevel::Normal, ""),
+        };
+        Counter { value, level, status: status.to_string() }
+    }
+
+    /// A counter that only matters while it's above zero, e.g. the number of ticks left stunned
+    fn active(value: i32, status: &str, level: CounterLevel) -> Counter {
+        match value {
+            v if v > 0 => Counter { value, level, status: status.to_string() },
+            _ => Counter { value, ..Default::default() },
+        }
+    }
+}
+
+#[derive(Default, Debug, Clone, Serialize, Deserialize)]
+pub struct Counters {
+    pub hunger: Counter,
+    pub thirst: Counter,
+    pub drowsiness: Counter,
+    pub exhaustion: Counter,
+    pub winded: Counter,
+    pub stunned: Counter,
+    pub unconscious: Counter,
+    pub suffocation: Counter,
+    pub webbed: Counter,
+    pub nausea: Counter,
+    pub dizziness: Counter,
+    pub pain: Counter,
+    pub paralysis: Counter,
+    pub numbness: Counter,
+    pub fever: Counter,
+    pub blood: Counter,
+    pub blood_max: i32,
+    /// (count, max)
+    pub limbs_stand: (i32, i32),
+    pub limbs_grasp: (i32, i32),
+}
+
+impl Counters {
+    pub unsafe fn new(df: &DFInstance, proc: &Process, unit_addr: usize) -> Counters {
+        let unit = |field: &str| unit_addr + df.memory_layout.field_offset(OffsetSection::Dwarf, field);
+        let c1 = unit("counters1");
+        let c2 = unit("counters2");
+        let c3 = unit("counters3");
+        let limbs = unit("limb_counters");
+        let blood = unit("blood");
+        // the counters themselves are offsets into those structs
+        let offset = |field: &str| df.memory_layout.field_offset(OffsetSection::UnitCounter, field);
+        let short = |addr: usize, field: &str| read_mem::<i16>(&proc.handle, addr + offset(field)) as i32;
+        let int = |addr: usize, field: &str| read_mem::<i32>(&proc.handle, addr + offset(field));
+
+        let blood_max = int(blood, "blood_max");
+        let blood_count = int(blood, "blood_count");
+        let blood_lost = match blood_max {
+            0 => 0,
+            max => 100 - blood_count * 100 / max,
+        };
+        let mut blood = Counter::rising(blood_lost, (100 - BLOOD_LOSS, "Lost blood"), (100 - SEVERE_BLOOD_LOSS, "Severe blood loss"));
+        blood.value = blood_count;
+
+        Counters {
+            hunger:      Counter::rising(int(c2, "hunger_timer"), (HUNGRY, "Hungry"), (STARVING, "Starving")),
+            thirst:      Counter::rising(int(c2, "thirst_timer"), (THIRSTY, "Thirsty"), (DEHYDRATED, "Dehydrated")),
+            drowsiness:  Counter::rising(int(c2, "sleepiness_timer"), (DROWSY, "Drowsy"), (VERY_DROWSY, "Very drowsy")),
+            exhaustion:  Counter::rising(int(c2, "exhaustion"), (TIRED, "Tired"), (OVER_EXERTED, "Over-exerted")),
+            paralysis:   Counter::active(int(c2, "paralysis"), "Paralyzed", CounterLevel::Critical),
+            numbness:    Counter::active(int(c2, "numbness"), "Numb", CounterLevel::Warning),
+            fever:       Counter::active(int(c2, "fever"), "Feverish", CounterLevel::Warning),
+            winded:      Counter::active(short(c1, "winded"), "Winded", CounterLevel::Warning),
+            stunned:     Counter::active(short(c1, "stunned"), "Stunned", CounterLevel::Warning),
+            unconscious: Counter::active(short(c1, "unconscious"), "Unconscious", CounterLevel::Critical),
+            suffocation: Counter::active(short(c1, "suffocation"), "Suffocating", CounterLevel::Critical),
+            webbed:      Counter::active(short(c1, "webbed"), "Webbed", CounterLevel::Warning),
+            pain:        Counter::rising(int(c3, "pain"), (1, "In pain"), (SEVERE_PAIN, "In severe pain")),
+            nausea:      Counter::active(int(c3, "nausea"), "Nauseous", CounterLevel::Warning),
+            dizziness:   Counter::active(int(c3, "dizziness"), "Dizzy", CounterLevel::Warning),
+            blood,
+            blood_max,
+            limbs_stand: (int(limbs, "limbs_stand_count"), int(limbs, "limbs_stand_max")),
+            limbs_grasp: (int(limbs, "limbs_grasp_count"), int(limbs, "limbs_grasp_max")),
+        }
+    }
+
+    fn all(&self) -> [&Counter; 16] {
+        [
+            &self.hunger, &self.thirst, &self.drowsiness, &self.exhaustion, &self.winded, &self.stunned,
+            &self.unconscious, &self.suffocation, &self.webbed, &self.nausea, &self.dizziness, &self.pain,
+            &self.paralysis, &self.numbness, &self.fever, &self.blood,
+        ]
+    }
+
+    /// The worst level of any counter
+    pub fn worst(&self) -> CounterLevel {
+        self.all().iter().map(|c| c.level).max().unwrap_or_default()
+    }
+
+    /// Labels of every counter that isn't normal, worst first
+    pub fn statuses(&self) -> Vec<String> {
+        let mut counters: Vec<&Counter> = self.all().into_iter().filter(|c| c.level != CounterLevel::Normal).collect();
+        counters.sort_by_key(|c| Reverse(c.level));
+        counters.iter().map(|c| c.status.clone()).collect()
+    }
+}
+
+/// A dwarf with a counter past its warning threshold
+#[derive(Default, Debug, Clone, Serialize, Deserialize)]
+pub struct CounterAlert {
+    pub dwarf_id: i32,
+    pub name: String,
+    pub level: CounterLevel,
+    pub statuses: Vec<String>,
+}
+
+/// Every dwarf with a counter past its warning threshold, worst first
+pub fn counter_alerts(dwarves: &[Dwarf]) -> Vec<CounterAlert> {
+    let mut alerts: Vec<CounterAlert> = dwarves.iter()
+        .filter(|d| d.counters.worst() != CounterLevel::Normal)
+        .map(|d| CounterAlert {
+            dwarf_id: d.id,
+            name: d.nice_name.clone(),
+            level: d.counters.worst(),
+            statuses: d.counters.statuses(),
+        })
+        .collect();
+    alerts.sort_by_key(|a| Reverse(a.level));
+    alerts
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn rising_counters_pass_their_thresholds() {
+        let hunger = |v| Counter::rising(v, (HUNGRY, "Hungry"), (STARVING, "Starving"));
+        assert_eq!(hunger(0).level, CounterLevel::Normal);
+        assert_eq!(hunger(0).status, "");
+        assert_eq!(hunger(HUNGRY - 1).level, CounterLevel::Normal);
+        assert_eq!(hunger(HUNGRY).level, CounterLevel::Warning);
+        assert_eq!(hunger(HUNGRY).status, "Hungry");
+        assert_eq!(hunger(STARVING).level, CounterLevel::Critical);
+        assert_eq!(hunger(STARVING + 1).status, "Starving");
+    }
+
+    #[test]
+    fn active_counters_only_count_above_zero() {
+        assert_eq!(Counter::active(0, "Stunned", CounterLevel::Warning).level, CounterLevel::Normal);
+        assert_eq!(Counter::active(-5, "Stunned", CounterLevel::Warning).status, "");
+        let stunned = Counter::active(3, "Stunned", CounterLevel::Warning);
+        assert_eq!((stunned.value, stunned.level, stunned.status.as_str()), (3, CounterLevel::Warning, "Stunned"));
+    }
+
+    #[test]
+    fn statuses_are_worst_first() {
+        let counters = Counters {
+            thirst: Counter::rising(THIRSTY, (THIRSTY, "Thirsty"), (DEHYDRATED, "Dehydrated")),
+            unconscious: Counter::active(10, "Unconscious", CounterLevel::Critical),
+            ..Default::default()
+        };
+        assert_eq!(counters.worst(), CounterLevel::Critical);
+        assert_eq!(counters.statuses(), vec!["Unconscious", "Thirsty"]);
+        assert_eq!(Counters::default().worst(), CounterLevel::Normal);
+        assert!(Counters::default().statuses().is_empty());
+    }
+}
@@ -128,6 +128,9 @@ pub struct Need {
     pub name: String,
     pub positive: String,
     pub negative: String,
+    /// prayer texts, with %1 standing for the deity's name
+    pub positive_deity: String,
+    pub negative_deity: String,
 }
 
 #[derive(Default, Serialize, Deserialize, Debug, Clone, PartialEq)]
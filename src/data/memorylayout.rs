@@ -27,12 +27,14 @@ pub enum OffsetSection {
     Dwarf,
     Syndrome,
     UnitWound,
+    UnitCounter,
     Soul,
     Need,
     Emotion,
     Job,
     Squad,
     Activity,
+    Location,
     Art,
     Viewscreen,
 }
@@ -59,12 +61,14 @@ pub struct MemoryOffsets {
     pub dwarf_offsets: HashMap<String, String>,
     pub syndrome_offsets: HashMap<String, String>,
     pub unit_wound_offsets: HashMap<String, String>,
+    pub unit_counter_offsets: HashMap<String, String>,
     pub soul_details: HashMap<String, String>,
     pub need_offsets: HashMap<String, String>,
     pub emotion_offsets: HashMap<String, String>,
     pub job_details: HashMap<String, String>,
     pub squad_offsets: HashMap<String, String>,
     pub activity_offsets: HashMap<String, String>,
+    pub location_offsets: HashMap<String, String>,
     pub art_offsets: HashMap<String, String>,
     pub viewscreen_offsets: HashMap<String, String>,
 }
@@ -110,12 +114,14 @@ pub fn get_section(&self, field: OffsetSection) -> Result<&HashMap<String, Strin
         OffsetSection::Dwarf => Ok(&self.dwarf_offsets),
         OffsetSection::Syndrome => Ok(&self.syndrome_offsets),
         OffsetSection::UnitWound => Ok(&self.unit_wound_offsets),
+        OffsetSection::UnitCounter => Ok(&self.unit_counter_offsets),
         OffsetSection::Soul => Ok(&self.soul_details),
         OffsetSection::Need => Ok(&self.need_offsets),
         OffsetSection::Emotion => Ok(&self.emotion_offsets),
         OffsetSection::Job => Ok(&self.job_details),
         OffsetSection::Squad => Ok(&self.squad_offsets),
         OffsetSection::Activity => Ok(&self.activity_offsets),
+        OffsetSection::Location => Ok(&self.location_offsets),
         OffsetSection::Art => Ok(&self.art_offsets),
         OffsetSection::Viewscreen => Ok(&self.viewscreen_offsets),
         _ => {
@@ -1,17 +1,23 @@
+use std::cmp::Reverse;
 use std::collections::HashMap;
 use std::error::Error;
 use serde::Serialize;
 use log::{info, error, debug};
-use crate::histfigure::FortressPosition;
-use crate::items::material::Material;
-use crate::items::ItemType;
-use crate::language::{Languages, Translation, Word};
+use crate::activity::Activity;
+use crate::artifact::Artifact;
+use crate::histevent::{EventQuery, HistEvent, HistEventDetail};
+use crate::histfigure::{FortressPosition, Veteran};
+use crate::items::material::{Material, Plant};
+use crate::items::{Item, ItemType};
+use crate::job::Reaction;
+use crate::location::{Location, Occupation};
+use crate::language::{Languages, Name, Translation, Word};
 use crate::logger::logger_display_name;
 use crate::squad::Squad;
 use crate::time::DfTime;
 use crate::util::global_address;
 use crate::race::race::Race;
-use crate::dwarf::dwarf::{Dwarf, print_dwarf};
+use crate::dwarf::dwarf::{Dwarf, UnitType, print_dwarf};
 
 use crate::util::memory::read_mem_as_string;
 use crate::data::{gamedata::{self, GameData}, memorylayout::{load_memory_layout, MemoryOffsets, OffsetSection}};
@@ -19,6 +25,13 @@ use crate::win;
 use crate::win::memory::memory::try_read_mem;
 use crate::win::{memory::memory::{mem_vec, read_mem}, process::Process};
 
+// material types are split into ranges, see DFInstance::get_material
+const BUILTIN_MATERIAL_COUNT: usize = 256;
+const CREATURE_MATERIAL_BASE: usize = 19;
+const HISTFIG_MATERIAL_BASE: usize = 219;
+const PLANT_MATERIAL_BASE: usize = 419;
+const MATERIAL_END: usize = 619;
+
 #[derive(Debug, Default, Serialize, Clone)]
 pub struct EmbarkOffsets {
     pub gview: usize,
@@ -57,17 +70,35 @@ pub struct DFInstance {
     pub poetry_vector: Vec<usize>,
     pub music_vector: Vec<usize>,
     pub dance_vector: Vec<usize>,
-    pub mapped_items: HashMap<usize, ItemType>,
+    pub plant_vector: Vec<usize>,
+    /// item id -> item address, for the items in item_vectors
+    pub mapped_items: HashMap<i32, usize>,
+    /// every item in item_vectors
+    pub items: Vec<Item>,
 
     pub base_materials: Vec<Material>,
     pub inorganic_materials: Vec<Material>,
+    pub plants: Vec<Plant>,
+    pub reactions: HashMap<String, Reaction>,
 
     pub creature_vector: Vec<usize>,
+    /// every unit in creature_vector, including animals, visitors and the dead
+    pub units: Vec<Dwarf>,
     pub syndromes_vector: Vec<usize>,
     pub historical_figures: HashMap<i32, usize>,
+    pub events_vector: Vec<usize>,
+    pub events: Vec<HistEvent>,
+    /// historical figure id -> indices into events
+    pub histfig_events: HashMap<i32, Vec<usize>>,
+    /// artifact id -> (creator histfig id, year created)
+    pub artifact_creators: HashMap<i32, (i32, i32)>,
+    pub artifacts: Vec<Artifact>,
     pub fake_identities_vector: Vec<usize>,
     pub squad_vector: Vec<usize>,
     pub squads: Vec<Squad>,
+    pub activities: Vec<Activity>,
+    pub fortress_site_id: i32,
+    pub locations: Vec<Location>,
     pub positions: HashMap<i32, FortressPosition>,
     pub nobles: HashMap<i32, FortressPosition>,
     pub beliefs: HashMap<usize, i32>,
@@ -126,75 +157,107 @@ impl DFInstance {
         self.dwarf_civ_id     = read_mem::<i32>(&proc.handle, global_address(proc, self.memory_layout.field_offset(OffsetSection::Addresses, "dwarf_civ_index")));
         self.creature_vector  = mem_vec(&proc.handle, global_address(proc, self.memory_layout.field_offset(OffsetSection::Addresses, "active_creature_vector")));
         self.syndromes_vector = mem_vec(&proc.handle, global_address(proc, self.memory_layout.field_offset(OffsetSection::Addresses, "all_syndromes_vector")));
+        self.events_vector    = mem_vec(&proc.handle, global_address(proc, self.memory_layout.field_offset(OffsetSection::Addresses, "events_vector")));
 
-        // TODO: fix materials
-        // df.load_materials(&proc);
-
-        self.load_item_definitions(&proc);
         self.load_arts(&proc);
+        self.load_materials(proc);
+        self.load_item_definitions(proc);
+        self.load_item_vectors(proc);
+        self.load_reactions(proc);
         self.load_languages(&proc);
         self.load_races(&proc);
         self.load_historical_figures(&proc);
+        self.load_items(proc);
+        self.load_events(proc);
+        self.load_artifacts(proc);
         self.load_historical_entities(&proc);
         self.load_beliefs(&proc);
+        self.load_squads(proc);
+        self.load_activities(proc);
+        self.load_locations(proc);
         self.data_loaded = true;
         Ok(())
     }
 
+    /// Loads the builtin, inorganic and plant materials. Creature materials are loaded with the races.
+    /// The raws don't change while a world is loaded, so this only reads them once.
     pub unsafe fn load_materials(&mut self, proc: &Process) {
+        let n = logger_display_name(&(self.logger_name.to_string() + "::load_materials"));
+        if !self.base_materials.is_empty() {
+            return;
+        }
+
         self.material_templates = mem_vec(&proc.handle, global_address(proc, self.memory_layout.field_offset(OffsetSection::Addresses, "material_templates_vector")));
 
-        let base_materials_addr = read_mem::<usize>(&proc.handle, global_address(proc, self.memory_layout.field_offset(OffsetSection::Addresses, "base_materials")));
-        for i in 0..255 {
-            let mat = Material::new(self, proc, i, base_materials_addr, true);
+        // base_materials is a fixed array of pointers, not a vector
+        let base_materials_addr = global_address(proc, self.memory_layout.field_offset(OffsetSection::Addresses, "base_materials"));
+        for i in 0..BUILTIN_MATERIAL_COUNT {
+            let addr = read_mem::<usize>(&proc.handle, base_materials_addr + i * size_of::<usize>());
+            let mat = match addr {
+                0 => Material{index: i as i32, ..Default::default()},
+                a => Material::new(self, proc, i, a, true),
+            };
             self.base_materials.push(mat);
         }
 
         let inorganics_vector = mem_vec(&proc.handle, global_address(proc, self.memory_layout.field_offset(OffsetSection::Addresses, "inorganics_vector")));
-        let mut i = 0;
-        for mat in inorganics_vector {
-            let mat = Material::new(self, proc, i, mat, false);
-            self.inorganic_materials.push(mat);
-            i += 1;
-        }
+        self.inorganic_materials = inorganics_vector.iter()
+            .enumerate()
+            .map(|(i, &m)| Material::new(self, proc, i, m, false))
+            .collect();
+
+        self.plants = self.plant_vector.iter()
+            .enumerate()
+            .map(|(i, &p)| Plant::new(self, proc, i, p))
+            .collect();
+
+        debug!("{n} | Loaded {} builtin, {} inorganic materials and {} plants",
+            self.base_materials.len(), self.inorganic_materials.len(), self.plants.len());
     }
 
-    pub fn get_material(&self, proc: &Process, mat_idx: i32, mat_type: i16, ) -> Material {
-        let mut mat = Material::default();
-
-        // raw material
-        if mat_idx < 0 {
-            mat = self.base_materials.get(mat_idx as usize).unwrap().clone();
-        } else if mat_type == 0 {
-            mat = self.inorganic_materials.get(mat_idx as usize).unwrap().clone();
-        } else if mat_type < 19 {
-            mat = self.base_materials.get(mat_idx as usize).unwrap().clone();
-        } else if mat_type < 219 {
-            let race = self.get_race(mat_idx);
-            if !race.is_none() {
-                mat = race.unwrap().creature_mats.get(mat_idx as usize).unwrap().clone();
-            }
-        } else if mat_type < 419 {
-            let histfig = self.historical_figures.get(&mat_idx);
-            if !histfig.is_none() {
-                unsafe {
-                let hist_race_bit = read_mem::<i16>(&proc.handle, histfig.unwrap() + self.memory_layout.field_offset(OffsetSection::HistFigure, "hist_race"));
-                let histfig_race: Race =  self.get_race(hist_race_bit as i32).unwrap().clone();
-                mat = histfig_race.creature_mats.get(mat_idx as usize).unwrap().clone();
-                }
-            }
+    /// Resolves a material from the `mat_type` and `mat_index` pair used throughout the game
+    pub unsafe fn get_material(&self, proc: &Process, mat_idx: i32, mat_type: i16) -> Option<&Material> {
+        if mat_type < 0 {
+            return None;
         }
-
-        // plant
-        if mat_idx < 619 {
-            // TODO plant
+        let mat_type = mat_type as usize;
+
+        match mat_type {
+            // builtin material, the index is unused
+            t if mat_idx < 0 => self.base_materials.get(t),
+            0 => self.inorganic_materials.get(mat_idx as usize),
+            t if t < CREATURE_MATERIAL_BASE => self.base_materials.get(t),
+            // the index is the race
+            t if t < HISTFIG_MATERIAL_BASE => {
+                self.get_race(mat_idx)?.creature_mats.get(t - CREATURE_MATERIAL_BASE)
+            },
+            // the index is the historical figure, the material comes from their race
+            t if t < PLANT_MATERIAL_BASE => {
+                let histfig = self.historical_figures.get(&mat_idx)?;
+                let race_id = read_mem::<i16>(&proc.handle, histfig + self.memory_layout.field_offset(OffsetSection::HistFigure, "hist_race"));
+                self.get_race(race_id as i32)?.creature_mats.get(t - HISTFIG_MATERIAL_BASE)
+            },
+            // the index is the plant
+            t if t < MATERIAL_END => {
+                self.plants.get(mat_idx as usize)?.materials.get(t - PLANT_MATERIAL_BASE)
+            },
+            _ => None,
         }
-        // NONE
-
-        return mat
-
     }
 
+    /// Reactions keyed by their tag, which is what jobs refer to
+    pub unsafe fn load_reactions(&mut self, proc: &Process) {
+        if !self.reactions.is_empty() {
+            return;
+        }
+        let reactions_vector = mem_vec(&proc.handle, global_address(proc, self.memory_layout.field_offset(OffsetSection::Addresses, "reactions_vector")));
+        self.reactions = reactions_vector.iter()
+            .map(|&r| {
+                let reaction = Reaction::new(self, proc, r);
+                (reaction.tag.clone(), reaction)
+            })
+            .collect();
+    }
 
     pub unsafe fn load_arts(&mut self, proc: &Process) {
         let arts = [
@@ -203,6 +266,7 @@ impl DFInstance {
             (&mut self.poetry_vector, "poetic_forms_vector"),
             (&mut self.music_vector, "musical_forms_vector"),
             (&mut self.dance_vector, "dance_forms_vector"),
+            (&mut self.plant_vector, "plants_vector"),
         ];
 
         for (vector, offset_name) in arts {
@@ -236,6 +300,45 @@ impl DFInstance {
         }
     }
 
+    pub unsafe fn load_item_vectors(&mut self, proc: &Process) {
+        // ItemType, field offset name
+        let item_types = [
+            (ItemType::Weapon, "weapons_vector"),
+            (ItemType::Shield, "shields_vector"),
+            (ItemType::Quiver, "quivers_vector"),
+            (ItemType::Crutch, "crutches_vector"),
+            (ItemType::Backpack, "backpacks_vector"),
+            (ItemType::Ammo, "ammo_vector"),
+            (ItemType::Flask, "flasks_vector"),
+            (ItemType::Pants, "pants_vector"),
+            (ItemType::Armor, "armor_vector"),
+            (ItemType::Shoes, "shoes_vector"),
+            (ItemType::Helm, "helms_vector"),
+            (ItemType::Gloves, "gloves_vector"),
+        ];
+
+        self.mapped_items.clear();
+        let id_offset = self.memory_layout.field_offset(OffsetSection::Item, "id");
+        for (item_type, offset_name) in item_types {
+            let items: Vec<usize> = mem_vec(&proc.handle, global_address(proc, self.memory_layout.field_offset(OffsetSection::Addresses, offset_name)));
+            for &addr in &items {
+                self.mapped_items.insert(read_mem::<i32>(&proc.handle, addr + id_offset), addr);
+            }
+            self.item_vectors.insert(item_type, items);
+        }
+    }
+
+    /// Reads the items in the item vectors. Materials of creature and historical figure items
+    /// come from the races and figures, so those have to be loaded first.
+    pub unsafe fn load_items(&mut self, proc: &Process) {
+        let addrs: Vec<usize> = self.item_vectors.values().flatten().copied().collect();
+        self.items = addrs.iter().map(|&a| Item::new(self, proc, a)).collect();
+    }
+
+    pub fn get_item_addr(&self, id: i32) -> Option<usize> {
+        self.mapped_items.get(&id).copied()
+    }
+
     pub unsafe fn load_historical_figures(&mut self, proc: &Process) {
         let hist_figs_addr = global_address(proc, self.memory_layout.field_offset(OffsetSection::Addresses, "historical_figures_vector"));
         let hist_figs_vector = mem_vec(&proc.handle, hist_figs_addr);
@@ -247,6 +350,103 @@ impl DFInstance {
         self.fake_identities_vector = mem_vec::<usize>(&proc.handle, global_address(proc, self.memory_layout.field_offset(OffsetSection::Addresses, "fake_identities_vector")));
     }
 
+    /// The native and English names of a historical figure, e.g. ("Urist Lokumolin", "Urist Inkfeast").
+    /// Both are empty if the figure isn't loaded.
+    pub unsafe fn get_histfig_names(&self, proc: &Process, id: i32) -> (String, String) {
+        let addr = match self.historical_figures.get(&id) {
+            Some(&a) => a,
+            None => return Default::default(),
+        };
+        self.read_names(proc, addr + self.memory_layout.field_offset(OffsetSection::HistFigure, "hist_name"))
+    }
+
+    /// Reads a language name in its native language and translated to English, e.g. a historical figure's
+    /// or an artifact's ("Ilasingshem Ngebzo", "The Bright Gem")
+    pub unsafe fn read_names(&self, proc: &Process, name_addr: usize) -> (String, String) {
+        let name = Name::new(self, proc, name_addr);
+        (self.languages.full_name(&name, false), self.languages.full_name(&name, true))
+    }
+
+    /// Reads a language name translated to English, e.g. "Urist Boltedrazor" or "The Bright Gem"
+    pub unsafe fn read_translated_name(&self, proc: &Process, name_addr: usize) -> String {
+        self.languages.full_name(&Name::new(self, proc, name_addr), true)
+    }
+
+    /// Reads the events added to the world since the last load. Events are only ever appended,
+    /// so everything is reread only when a different world is loaded.
+    pub unsafe fn load_events(&mut self, proc: &Process) {
+        let same_world = self.events.first().map(|e| e.addr) == self.events_vector.first().copied()
+            && self.events.len() <= self.events_vector.len();
+        if !same_world {
+            self.events.clear();
+            self.histfig_events.clear();
+        }
+
+        let start = self.events.len();
+        let new_events: Vec<HistEvent> = self.events_vector[start..].iter().map(|&e| HistEvent::new(self, proc, e)).collect();
+        for (i, e) in new_events.iter().enumerate() {
+            for &id in &e.histfig_ids {
+                self.histfig_events.entry(id).or_default().push(start + i);
+            }
+        }
+        self.events.extend(new_events);
+    }
+
+    pub unsafe fn load_artifacts(&mut self, proc: &Process) {
+        self.artifact_creators = self.events.iter().filter_map(|e| match e.detail {
+            HistEventDetail::ArtifactCreated { artifact_id, creator_id } => Some((artifact_id, (creator_id, e.year))),
+            _ => None,
+        }).collect();
+
+        let artifacts_vector = mem_vec(&proc.handle, global_address(proc, self.memory_layout.field_offset(OffsetSection::Addresses, "artifacts_vector")));
+        self.artifacts = artifacts_vector.iter().map(|&a| Artifact::new(self, proc, a)).collect();
+    }
+
+    /// Fills in the artifact links that need the dwarves to be loaded first
+    fn link_artifacts(&mut self) {
+        for a in self.artifacts.iter_mut() {
+            a.creator_dwarf_id = self.dwarves.iter().find(|d| d.histfig_id >= 0 && d.histfig_id == a.creator_id).map_or(-1, |d| d.id);
+            a.holder_name = self.dwarves.iter().find(|d| d.id == a.holder_id).map(|d| d.nice_name.clone()).unwrap_or_default();
+        }
+    }
+
+    /// Finds a historical event by id. Events are stored in id order.
+    pub fn find_event(&self, id: i32) -> Option<&HistEvent> {
+        let idx = self.events.binary_search_by_key(&id, |e| e.id).ok()?;
+        self.events.get(idx)
+    }
+
+    /// One page of the events matching a query, see `EventQuery` for the paging
+    pub fn query_events(&self, query: &EventQuery) -> Vec<HistEvent> {
+        let matching: Box<dyn Iterator<Item = &HistEvent>> = match query.histfig_id {
+            Some(id) => Box::new(self.histfig_events.get(&id).into_iter().flatten().filter_map(|&i| self.events.get(i))),
+            None => Box::new(self.events.iter()),
+        };
+        matching
+            .filter(|e| query.matches(e))
+            .skip(query.offset.unwrap_or(0))
+            .take(query.limit())
+            .cloned()
+            .collect()
+    }
+
+    /// Dwarves with at least one kill, most kills first
+    pub fn veterans(&self) -> Vec<Veteran> {
+        let mut veterans: Vec<Veteran> = self.dwarves.iter()
+            .filter(|d| d.histfig.total_kills() > 0)
+            .map(|d| Veteran {
+                dwarf_id: d.id,
+                name: d.nice_name.clone(),
+                squad_name: d.pending_squad_name.clone(),
+                total_kills: d.histfig.total_kills(),
+                notable_kills: d.histfig.kills.clone(),
+                kill_counts: d.histfig.kill_counts.clone(),
+            })
+            .collect();
+        veterans.sort_by_key(|v| Reverse(v.total_kills));
+        veterans
+    }
+
     pub unsafe fn get_fake_identity(&self, id: i32) -> Option<i32> {
         for f in &self.fake_identities_vector {
             if *f == id as usize{
@@ -339,7 +539,14 @@ impl DFInstance {
             if !races_vector.is_empty() {
                 let mut id: i32 = 0;
                 for ptr in races_vector {
-                    let race = Race::new(self, proc, id, ptr).unwrap();
+                    let mut race = Race::new(self, proc, id, ptr).unwrap();
+                    // creature materials don't change, so reuse them if the race was already loaded
+                    match self.races.get(id as usize) {
+                        Some(old) if old.name == race.name && !old.creature_mats.is_empty() => {
+                            race.creature_mats = old.creature_mats.clone();
+                        },
+                        _ => race.load_materials(self, proc),
+                    }
                     races.push(race);
                     id += 1;
                 }
@@ -358,47 +565,122 @@ impl DFInstance {
         self.squads = self.squad_vector.iter().map(|&s| Squad::new(self, proc, s)).collect();
     }
 
-    pub unsafe fn load_dwarves(&mut self, proc: &Process) -> Result<(), Box<dyn Error>> {
-        let n = logger_display_name(&(self.logger_name.to_string() + "::load_dwarves"));
+    /// Moves a dwarf into a squad slot, taking them out of their current squad first
+    pub unsafe fn assign_to_squad(&mut self, proc: &Process, dwarf_id: i32, squad_id: i32, position: Option<i32>) -> Result<(), Box<dyn Error>> {
+        let n = logger_display_name(&(self.logger_name.to_string() + "::assign_to_squad"));
+        let d_idx = self.dwarves.iter().position(|d| d.id == dwarf_id).ok_or(format!("{n} | No dwarf with id {dwarf_id}"))?;
+        let s_idx = self.squads.iter().position(|s| s.id == squad_id).ok_or(format!("{n} | No squad with id {squad_id}"))?;
+
+        let mut dwarf = self.dwarves[d_idx].clone();
+        let mut squad = self.squads[s_idx].clone();
+        let position = match position {
+            Some(p) => p,
+            None => squad.free_position().ok_or(format!("{n} | {} has no free positions", squad.name))?,
+        };
 
-        match self.creature_vector.is_empty() {
-            false => {
-                self.dwarves = self.creature_vector.iter().filter_map(|&c| {
-                    match Dwarf::new(self, proc, c) {
-                        Ok(d) => {
-                            Some(d)
-                        },
-                        Err(e) => {
-                            // error!("{n} | Failed to load dwarf: {}", e);
-                            None
-                        }
-                    }
-                }).collect();
-            },
-            true => {
-                if self.is_on_embark_screen(proc) {
-                    info!("{n} | Loading dwarves from embark screen...");
-                    self.dwarves = mem_vec(&proc.handle, self.embark_offsets.final_embark).iter().filter_map(|&c| {
-                        match Dwarf::new(self, proc, c) {
-                            Ok(d) => {
-                                Some(d)
-                            },
-                            Err(e) => {
-                                // error!("{n} | Failed to load dwarf: {}", e);
-                                None
-                            }
-                        }
-                    }).collect();
-                }
+        // nothing is written until the move is known to be allowed
+        squad.can_assign(self, &dwarf, position)?;
+        if dwarf.squad.addr != 0 && dwarf.squad.id != squad_id {
+            self.remove_from_squad(proc, dwarf_id)?;
+            dwarf = self.dwarves[d_idx].clone();
+        }
+
+        squad.assign_member(self, proc, &mut dwarf, position)?;
+        self.squads[s_idx] = squad;
+        self.update_dwarf(d_idx, dwarf);
+        Ok(())
+    }
+
+    /// Removes a dwarf from whichever squad they are in
+    pub unsafe fn remove_from_squad(&mut self, proc: &Process, dwarf_id: i32) -> Result<(), Box<dyn Error>> {
+        let n = logger_display_name(&(self.logger_name.to_string() + "::remove_from_squad"));
+        let d_idx = self.dwarves.iter().position(|d| d.id == dwarf_id).ok_or(format!("{n} | No dwarf with id {dwarf_id}"))?;
+
+        let mut dwarf = self.dwarves[d_idx].clone();
+        let s_idx = self.squads.iter().position(|s| s.addr != 0 && s.addr == dwarf.squad.addr)
+            .ok_or(format!("{n} | {} is not in a squad", dwarf.first_name))?;
+
+        let mut squad = self.squads[s_idx].clone();
+        squad.remove_member(self, proc, &mut dwarf)?;
+        self.squads[s_idx] = squad;
+        self.update_dwarf(d_idx, dwarf);
+        Ok(())
+    }
+
+    pub unsafe fn load_activities(&mut self, proc: &Process) {
+        let activities_vector = mem_vec(&proc.handle, global_address(proc, self.memory_layout.field_offset(OffsetSection::Addresses, "activities_vector")));
+        self.activities = activities_vector.iter().map(|&a| Activity::new(self, proc, a)).collect();
+    }
+
+    /// Loads the taverns, temples and other locations of the fortress site with their occupations
+    pub unsafe fn load_locations(&mut self, proc: &Process) {
+        let world_data = read_mem::<usize>(&proc.handle, global_address(proc, self.memory_layout.field_offset(OffsetSection::Addresses, "world_data")));
+        let sites = mem_vec::<usize>(&proc.handle, world_data + self.memory_layout.field_offset(OffsetSection::Addresses, "active_sites_vector"));
+
+        // the player's fortress is the only site of type 0
+        let site = match sites.iter().find(|&&s| read_mem::<i16>(&proc.handle, s + self.memory_layout.field_offset(OffsetSection::Addresses, "world_site_type")) == 0) {
+            Some(&s) => s,
+            None => {
+                self.locations.clear();
+                return;
+            }
+        };
+        self.fortress_site_id = read_mem::<i32>(&proc.handle, site + self.memory_layout.field_offset(OffsetSection::Addresses, "world_site_id"));
+        self.locations = mem_vec::<usize>(&proc.handle, site + self.memory_layout.field_offset(OffsetSection::Addresses, "world_site_buildings"))
+            .iter()
+            .map(|&b| Location::new(self, proc, b))
+            .collect();
+
+        let occupations: Vec<Occupation> = mem_vec::<usize>(&proc.handle, global_address(proc, self.memory_layout.field_offset(OffsetSection::Addresses, "occupations_vector")))
+            .iter()
+            .map(|&o| Occupation::new(self, proc, o))
+            .filter(|o| o.site_id == self.fortress_site_id)
+            .collect();
+        for o in occupations {
+            if let Some(l) = self.locations.iter_mut().find(|l| l.id == o.location_id) {
+                l.occupations.push(o);
             }
         }
+    }
+
+    /// Loads every unit on the map, then picks out the fortress' dwarves
+    pub unsafe fn load_units(&mut self, proc: &Process) -> Result<(), Box<dyn Error>> {
+        let n = logger_display_name(&(self.logger_name.to_string() + "::load_units"));
+
+        let unit_addrs = match self.creature_vector.is_empty() {
+            false => self.creature_vector.clone(),
+            true if self.is_on_embark_screen(proc) => {
+                info!("{n} | Loading dwarves from embark screen...");
+                mem_vec(&proc.handle, self.embark_offsets.final_embark)
+            },
+            true => vec![],
+        };
+        self.units = unit_addrs.iter().filter_map(|&c| Dwarf::new(self, proc, c).ok()).collect();
+        self.dwarves = self.units.iter()
+            .filter(|u| u.unit_type == UnitType::Citizen && u.race.id == self.dwarf_race_id && !u.has_left())
+            .cloned()
+            .collect();
 
+        self.link_artifacts();
         match self.dwarves.is_empty() {
             false => Ok(()),
             true => Err(format!("{n} | Dwarves empty, No dwarves loaded").into())
         }
     }
 
+    /// Units of one type, e.g. every visitor
+    pub fn units_of_type(&self, unit_type: UnitType) -> Vec<&Dwarf> {
+        self.units.iter().filter(|u| u.unit_type == unit_type).collect()
+    }
+
+    /// Stores a changed dwarf in both the dwarf and unit lists
+    fn update_dwarf(&mut self, d_idx: usize, dwarf: Dwarf) {
+        if let Some(u) = self.units.iter_mut().find(|u| u.id == dwarf.id) {
+            *u = dwarf.clone();
+        }
+        self.dwarves[d_idx] = dwarf;
+    }
+
     pub unsafe fn is_on_embark_screen(&mut self, proc: &Process) -> bool {
         debug!("Checking embark screen");
         const MAX_DEPTH: usize = 5;
@@ -9,29 +9,44 @@ pub mod dwarf {
     use serde::Deserialize;
     use serde::Serialize;
 
+    use crate::activity::ActivityEvent;
     use crate::attribute::{Attribute, AttributeType};
     use crate::logger::logger_display_name;
     use crate::skill::Skill;
     use crate::thought::Thought;
     use crate::histfigure::FortressPosition;
-    use crate::need::Need;
+    use crate::job::Job;
+    use crate::language::Name;
+    use crate::location::Occupation;
+    use crate::knowledge::Research;
+    use crate::mood::MoodInfo;
+    use crate::need::{Focus, Need};
     use crate::preference::Commitment;
     use crate::preference::Orientation;
     use crate::preference::Preference;
+    use crate::preference::PreferenceType;
     use crate::squad::Squad;
+    use crate::squad::SquadOrderType;
     use crate::syndromes::Curse;
     use crate::syndromes::CurseType;
     use crate::syndromes::Syndrome;
     use crate::time::DfTime;
+    use crate::unitflags::{UnitFlag1, UnitFlag2, UnitFlag3, STATUS_FLAGS1, STATUS_FLAGS2, STATUS_FLAGS3};
+    use crate::uniform::EquipmentIssue;
     use crate::caste::caste::Caste;
     use crate::data::gamedata::*;
     use crate::data::memorylayout::*;
+    use crate::counters::Counters;
+    use crate::health::Health;
     use crate::histfigure::HistoricalFigure;
+    use crate::inventory::InventoryItem;
     use crate::race::race::Race;
+    use crate::religion::Worship;
     use crate::win::memory::memory::mem_vec;
     use crate::win::memory::memory::read_mem;
     use crate::win::memory::memory::read_raw;
     use crate::win::process::Process;
+    use crate::util::capitalize_each;
     use crate::{util::memory::read_mem_as_string, DFInstance};
 
     #[derive(Default, Serialize, Deserialize, Clone, Debug)]
@@ -39,6 +54,16 @@ pub mod dwarf {
         pub addr: usize,
         pub id: i32,
         pub civ_id: i32,
+        pub unit_type: UnitType,
+        pub flags1: u32,
+        pub flags2: u32,
+        pub flags3: u32,
+        /// the names of the notable flags that are set, e.g. "Caged" or "Merchant"
+        pub statuses: Vec<String>,
+        /// the animal training level, WILD_UNTAMED for anything that isn't a trained animal
+        pub animal_type: i16,
+        /// the unit id of a pet's owner, -1 if none
+        pub pet_owner_id: i32,
         pub raw_prof_id: u8,
         pub histfig_id: i32,
         pub histfig: HistoricalFigure,
@@ -76,19 +101,35 @@ pub mod dwarf {
         pub belief_trait_conflicts: Vec<i32>,
         pub traits: Vec<(i32, String, i16)>, // id, name, value
         pub trait_belief_conflicts: Vec<i32>,
+        /// how unusual each trait is for the caste, -100 to 100 by trait id
+        pub trait_extremes: HashMap<i32, i32>,
         pub goals: Vec<(Goal, i16)>,
         pub goals_realized: i32,
         pub thought_ids: Vec<i32>,
         pub thoughts: Vec<Thought>,
         pub needs: Vec<Need>,
+        pub focus: Focus,
+        /// the deities this dwarf worships, strongest first
+        pub worship: Vec<Worship>,
+        pub preferences: Vec<Preference>,
         pub skills: Vec<Skill>,
         pub stress_level: i32,
         pub happiness_level: HappinessLevel,
         pub mood: Mood,
         pub locked_mood: bool,
+        pub had_mood: bool,
+        /// the name of the artifact made in a strange mood
+        pub artifact_name: String,
+        pub translated_artifact_name: String,
+        /// ids of the artifacts this dwarf created
+        pub artifacts: Vec<i32>,
+        pub strange_mood: Option<MoodInfo>,
         pub syndromes: Vec<Syndrome>,
         pub is_cursed: bool,
         pub curse: Curse,
+        /// the race a syndrome is turning this dwarf into, -1 if none
+        pub transform_race_id: i32,
+        pub transform_race: String,
 
         pub squad: Squad,
         pub squad_position: i32,
@@ -98,6 +139,25 @@ pub mod dwarf {
 
         pub noble_position: FortressPosition,
         pub labors: HashMap<i32, Labor>,
+
+        pub current_job: Job,
+        pub is_idle: bool,
+        pub on_break: bool,
+        pub activities: Vec<ActivityEvent>,
+        /// topics this dwarf is currently studying or teaching
+        pub research: Vec<Research>,
+        /// jobs at the fortress's taverns, temples, libraries and guildhalls
+        pub occupations: Vec<Occupation>,
+
+        pub health: Health,
+        /// hunger, thirst, sleep and the other counters, with a status label for each
+        pub counters: Counters,
+        pub inventory: Vec<InventoryItem>,
+        /// differences between the squad uniform and the inventory
+        pub equipment_issues: Vec<EquipmentIssue>,
+        /// ids of the historical events this dwarf took part in, oldest first.
+        /// `/events?histfig_id=` describes them.
+        pub biography: Vec<i32>,
     }
 
     impl Dwarf {
@@ -107,22 +167,20 @@ pub mod dwarf {
                 addr,
                 id:     read_mem::<i32>(&proc.handle, addr + df.memory_layout.field_offset(OffsetSection::Dwarf, "id")),
                 civ_id: read_mem::<i32>(&proc.handle, addr + df.memory_layout.field_offset(OffsetSection::Dwarf, "civ")),
+                flags1: read_mem::<u32>(&proc.handle, addr + df.memory_layout.field_offset(OffsetSection::Dwarf, "flags1")),
+                flags2: read_mem::<u32>(&proc.handle, addr + df.memory_layout.field_offset(OffsetSection::Dwarf, "flags2")),
+                flags3: read_mem::<u32>(&proc.handle, addr + df.memory_layout.field_offset(OffsetSection::Dwarf, "flags3")),
+                animal_type:  read_mem::<i16>(&proc.handle, addr + df.memory_layout.field_offset(OffsetSection::Dwarf, "animal_type")),
+                pet_owner_id: read_mem::<i32>(&proc.handle, addr + df.memory_layout.field_offset(OffsetSection::Dwarf, "pet_owner_id")),
                 ..Default::default()
             };
 
-            // check if the creature is from the same civ as the fort
-            if d.civ_id != df.dwarf_civ_id {
-                // debug!("{n} | Unit is from the wrong civ.");
+            if d.read_race_and_caste(df, proc).is_err() {
+                debug!("{n} | Unit {} has an unknown race or caste.", d.id);
                 return Err(Error);
             }
-
-            match d.read_race_and_caste(df, proc) {
-                Ok(_) => (),
-                Err(_) => {
-                    // debug!("{n} | Unit is not a Dwarf.");
-                    return Err(Error);
-                }
-            }
+            d.unit_type = UnitType::classify(&d, df.dwarf_civ_id);
+            d.read_statuses();
 
             d.read_names(df, proc);
             d.read_states(df, proc);
@@ -130,24 +188,34 @@ pub mod dwarf {
             d.read_age(df, proc);
             d.read_historical_figure(df, proc);
             d.read_fake_identity();
+            d.read_biography(df);
             d.read_squad(df, proc);
-            // TODO: current job
+            d.read_activities(df);
+            d.read_occupations(df);
+            d.read_current_job(df, proc);
             d.read_labors(df, proc);
-            // TODO: uniform
+            d.inventory = InventoryItem::read_all(df, proc, d.addr, &d.caste);
+            d.read_uniform(df, proc);
             d.read_body_size(df, proc);
+            d.health = Health::new(df, proc, d.addr, &d.caste);
+            d.counters = Counters::new(df, proc, d.addr);
             d.read_syndromes(df, proc);
             d.read_soul(df, proc);
-            d.read_traits(df, proc);
-            d.read_mood(df, proc);
-            d.read_emotions(df, proc);
-            d.read_beliefs(df, proc);
-            d.read_goals(df, proc);
-            d.read_needs(df, proc);
-            d.read_gender_orientation(df, proc);
             d.read_noble_position(df);
-            d.read_preferences(df, proc);
             d.read_attributes(df, proc);
-            d.read_skills(df, proc);
+            // animals and some other creatures have no soul to read a personality from
+            if !d.souls.is_empty() {
+                d.read_traits(df, proc);
+                d.read_mood(df, proc);
+                d.read_emotions(df, proc);
+                d.read_beliefs(df, proc);
+                d.read_goals(df, proc);
+                d.read_needs(df, proc);
+                d.worship = Worship::for_dwarf(df, proc, &d);
+                d.read_gender_orientation(df, proc);
+                d.read_preferences(df, proc);
+                d.read_skills(df, proc);
+            }
             // loading the dwarves themselves is handled by the update_task
             Ok(d)
         }
@@ -171,7 +239,11 @@ pub mod dwarf {
             }
 
             // Mental attributes
-            let mut mental_attr_addr = self.souls[0] + df.memory_layout.field_offset(OffsetSection::Soul, "mental_attrs");
+            let soul = match self.souls.first() {
+                Some(&s) => s,
+                None => return,
+            };
+            let mut mental_attr_addr = soul + df.memory_layout.field_offset(OffsetSection::Soul, "mental_attrs");
             let mental_attributes = [
                 AttributeType::AnalyticalAbility,
                 AttributeType::Focus,
@@ -194,30 +266,30 @@ pub mod dwarf {
             }
         }
 
-        #[allow(unused_variables)]
         pub unsafe fn load_attribute(&mut self, df: &DFInstance, proc: &Process, addr: usize, attr_type: AttributeType) {
-            let cti = 500;
-            // let desc: Hashmap<i32, String>
-
             let value = read_mem::<i32>(&proc.handle, addr);
             let max = read_mem::<i32>(&proc.handle, addr + 0x4);
-            let display_value = value;
+            let mut display_value = value;
 
-            // TODO: permanent syndromes
-            // TODO: temporary syndromes
-            // TODO: caste?
-            // TODO: baby/animal
-            // TODO: syndrome names
+            // permanent changes first so temporary buffs apply to the changed value
+            let mut syndromes: Vec<&Syndrome> = self.syndromes.iter()
+                .filter(|s| s.attribute_changes.contains_key(&(attr_type as i32)))
+                .collect();
+            syndromes.sort_by_key(|s| !s.is_permanent);
+            for s in &syndromes {
+                display_value = s.attribute_changes[&(attr_type as i32)].apply(display_value);
+            }
 
-            let a = Attribute{
+            let mut a = Attribute{
                 id: attr_type as i32,
                 name: attr_type.to_string(),
                 value,
                 display_value,
                 max,
-                cti,
+                syndromes: syndromes.iter().map(|s| s.name.clone()).collect(),
                 ..Default::default()
             };
+            a.calculate(&df.game_data, &self.caste);
 
             self.attributes.insert(attr_type as i32, a);
         }
@@ -242,6 +314,7 @@ pub mod dwarf {
         }
 
         unsafe fn read_syndromes(&mut self, df: &DFInstance, proc: &Process) {
+            self.transform_race_id = -1;
             self.syndromes = mem_vec(&proc.handle, self.addr + df.memory_layout.field_offset(OffsetSection::Dwarf, "active_syndrome_vector")).iter()
                 .map(|&s| Syndrome::new(df, proc, s))
                 .collect();
@@ -273,9 +346,9 @@ pub mod dwarf {
 
                 if s.has_transform == true {
                     let race_id = s.transform_race;
-                    if race_id >= 0 {
-                        // TODO: transform
-                        let trans_race = df.races.iter().find(|&x| x.id == race_id).unwrap();
+                    if let Some(trans_race) = df.get_race(race_id) {
+                        self.transform_race_id = race_id;
+                        self.transform_race = trans_race.name.clone();
                         // TODO: crazed night creature
                     }
                 }
@@ -287,14 +360,107 @@ pub mod dwarf {
             self.squad_position = read_mem::<i32>(&proc.handle, self.addr + df.memory_layout.field_offset(OffsetSection::Dwarf, "squad_position"));
             self.pending_squad_position = self.squad_position;
 
+            self.pending_squad_id = squad_id;
+
             if squad_id >= 0 {// && animal, adult
-                let s = df.squads.iter().find(|&x| x.id == squad_id).unwrap();
-                if s.id != 0 {
+                if let Some(s) = df.squads.iter().find(|&x| x.id == squad_id) {
                     self.squad = s.clone();
+                    self.pending_squad_name = s.name.clone();
+                }
+            }
+        }
+
+        /// Events this dwarf is taking part in, named for their role in them
+        pub fn read_activities(&mut self, df: &DFInstance) {
+            self.activities = df.activities.iter()
+                .flat_map(|a| a.events.iter())
+                .filter(|e| e.has_participant(self.histfig_id, self.id))
+                .map(|e| {
+                    let mut event = e.clone();
+                    event.name = e.name_for(df, self.histfig_id);
+                    event
+                })
+                .collect();
+            self.research = Research::from_activities(df, &self.activities);
+        }
+
+        pub fn read_occupations(&mut self, df: &DFInstance) {
+            self.occupations = df.locations.iter()
+                .flat_map(|l| l.occupations.iter())
+                .filter(|o| o.unit_id == self.id)
+                .cloned()
+                .collect();
+        }
+
+        unsafe fn read_current_job(&mut self, df: &DFInstance, proc: &Process) {
+            self.on_break = Job::is_on_break(&self.states);
+            match Job::new(df, proc, self.addr) {
+                Some(job) => {
+                    self.current_job = job;
+                    self.is_idle = false;
+                },
+                None => {
+                    self.current_job = Job::idle(df, self.on_break);
+                    // soldiers waiting on orders don't have a job either
+                    let has_orders = self.squad.addr != 0 && (
+                        self.squad.orders.contains_key(&self.histfig_id) ||
+                        self.squad.squad_order != SquadOrderType::None
+                    );
+                    self.is_idle = !self.on_break && !has_orders && self.activities.is_empty();
                 }
             }
         }
 
+        unsafe fn read_uniform(&mut self, df: &DFInstance, proc: &Process) {
+            if let Some(uniform) = self.squad.uniforms.get(&self.squad_position) {
+                self.equipment_issues = uniform.check(df, proc, &self.inventory, self.squad.ammo_each, self.squad.carry_food, self.squad.carry_water);
+            }
+        }
+
+        pub fn has_flag1(&self, flag: UnitFlag1) -> bool {
+            self.flags1 & (1 << flag as u32) != 0
+        }
+
+        pub fn has_flag2(&self, flag: UnitFlag2) -> bool {
+            self.flags2 & (1 << flag as u32) != 0
+        }
+
+        pub fn has_flag3(&self, flag: UnitFlag3) -> bool {
+            self.flags3 & (1 << flag as u32) != 0
+        }
+
+        pub fn set_flag1(&mut self, flag: UnitFlag1) {
+            self.flags1 |= 1 << flag as u32;
+        }
+
+        pub fn set_flag2(&mut self, flag: UnitFlag2) {
+            self.flags2 |= 1 << flag as u32;
+        }
+
+        pub fn is_dead(&self) -> bool {
+            self.has_flag1(UnitFlag1::Dead) || self.has_flag2(UnitFlag2::Killed) || self.has_flag3(UnitFlag3::Ghostly)
+        }
+
+        /// Units that left the map stay in the creature vector
+        pub fn has_left(&self) -> bool {
+            self.has_flag1(UnitFlag1::Left)
+        }
+
+        fn read_statuses(&mut self) {
+            self.statuses = STATUS_FLAGS1.iter().filter(|&&f| self.has_flag1(f)).map(|f| format!("{f:?}"))
+                .chain(STATUS_FLAGS2.iter().filter(|&&f| self.has_flag2(f)).map(|f| format!("{f:?}")))
+                .chain(STATUS_FLAGS3.iter().filter(|&&f| self.has_flag3(f)).map(|f| format!("{f:?}")))
+                .collect();
+        }
+
+        /// Babies and children can't be drafted
+        pub fn is_adult(&self) -> bool {
+            if self.profession.name == "Baby" || self.profession.name == "Child" {
+                return false;
+            }
+            self.age >= self.caste.child_age as u64
+        }
+
         unsafe fn read_age(&mut self, df: &DFInstance, proc: &Process) {
             let mut birth_year = read_mem::<i32>(&proc.handle, self.addr + df.memory_layout.field_offset(OffsetSection::Dwarf, "birth_year"));
             let mut birth_time = read_mem::<i32>(&proc.handle, self.addr + df.memory_layout.field_offset(OffsetSection::Dwarf, "birth_time"));
@@ -318,6 +484,14 @@ pub mod dwarf {
             }
         }
 
+        fn read_biography(&mut self, df: &DFInstance) {
+            let idxs = match df.histfig_events.get(&self.histfig_id) {
+                Some(i) if self.histfig_id >= 0 => i,
+                _ => return,
+            };
+            self.biography = idxs.iter().filter_map(|&i| df.events.get(i)).map(|e| e.id).collect();
+        }
+
         unsafe fn read_fake_identity(&mut self) {
             if self.histfig.has_fake_identity {
                 self.real_name = self.nice_name.clone();
@@ -325,10 +499,12 @@ pub mod dwarf {
 
                 self.first_name = self.histfig.fake_identity.fake_name.clone();
                 self.nickname = self.histfig.fake_identity.fake_nickname.clone();
+                self.last_name = self.histfig.fake_identity.fake_last_name.clone();
+                self.translated_last_name = self.histfig.fake_identity.fake_translated_last_name.clone();
+                self.build_names();
                 let fake_birth_year = DfTime::from_years(self.histfig.fake_identity.fake_birth_year as u64);
                 let fake_birth_time = DfTime::from_seconds(self.histfig.fake_identity.fake_birth_time as u64);
                 self.birth_date = fake_birth_year + fake_birth_time;
-                // TODO: last name
             }
         }
 
@@ -361,19 +537,9 @@ pub mod dwarf {
 
         unsafe fn read_race_and_caste(&mut self, df: &DFInstance, proc: &Process) -> Result<(), Error> {
             let race_id = read_mem::<i32>(&proc.handle, self.addr + df.memory_layout.field_offset(OffsetSection::Dwarf, "race"));
-            let race = df.get_race(race_id).unwrap();
-            if race.name != "dwarf" {
-                return Err(Error);
-            }
-
-            // I'm pretty sure this doesn't work as intended but dwarves only have 2 castes so it doesn't matter for now
-            let caste_id = read_mem::<i32>(&proc.handle, self.addr + df.memory_layout.field_offset(OffsetSection::Dwarf, "caste"));
-            let caste: &Caste;
-            if caste_id == 0 {
-                caste = &race.castes[0];
-            } else {
-                caste = &race.castes[1];
-            }
+            let race = df.get_race(race_id).ok_or(Error)?;
+            let caste_id = read_mem::<i16>(&proc.handle, self.addr + df.memory_layout.field_offset(OffsetSection::Dwarf, "caste"));
+            let caste: &Caste = race.castes.get(caste_id as usize).filter(|_| caste_id >= 0).ok_or(Error)?;
 
             // I think I only need to clone because I'm bad at lifetimes.
             self.race = race.clone();
@@ -393,15 +559,26 @@ pub mod dwarf {
         }
 
         pub unsafe fn read_names(&mut self, df: &DFInstance, proc: &Process) {
-            let name_offset =  self.addr + df.memory_layout.field_offset(OffsetSection::Dwarf, "name");
-            self.last_name = df.languages.language_word(df, proc, name_offset);
-            self.first_name = read_mem_as_string(&proc, name_offset + df.memory_layout.field_offset(OffsetSection::Word, "first_name"));
-            self.nickname = read_mem_as_string(&proc, name_offset + df.memory_layout.field_offset(OffsetSection::Word, "nickname"));
-            // TODO: translated last name
-        }
-
-        pub unsafe fn read_last_name(df: &DFInstance, proc: &Process, offset: usize) -> String {
-            df.languages.language_word(df, proc, offset)
+            let name = Name::new(df, proc, self.addr + df.memory_layout.field_offset(OffsetSection::Dwarf, "name"));
+            self.first_name = capitalize_each(&name.first_name);
+            self.nickname = name.nickname.clone();
+            self.last_name = df.languages.last_name(&name, false);
+            self.translated_last_name = df.languages.last_name(&name, true);
+            self.build_names();
+        }
+
+        /// e.g. "Urist 'Bob' Lolokzefon" and "Urist Boltedrazor"
+        fn build_names(&mut self) {
+            let nickname = match self.nickname.is_empty() {
+                true => String::new(),
+                false => format!("'{}'", self.nickname),
+            };
+            self.nice_name = [&self.first_name, &nickname, &self.last_name].iter()
+                .filter(|p| !p.is_empty())
+                .map(|p| p.as_str())
+                .collect::<Vec<&str>>()
+                .join(" ");
+            self.translated_name = format!("{} {}", self.first_name, self.translated_last_name).trim().to_string();
         }
 
         pub unsafe fn read_profession(&mut self, df: &DFInstance, proc: &Process) {
@@ -416,7 +593,9 @@ pub mod dwarf {
                 println!("Dwarf has more than one soul");
             }
             // get personality from the first soul
-            self.personality_addr = self.souls[0] + df.memory_layout.field_offset(OffsetSection::Soul, "personality");
+            if let Some(&soul) = self.souls.first() {
+                self.personality_addr = soul + df.memory_layout.field_offset(OffsetSection::Soul, "personality");
+            }
             // TODO: consider consolidating traits/goals/beliefs/needs/preferences into soul since personality_addr is defined by soul
         }
 
@@ -424,10 +603,9 @@ pub mod dwarf {
             self.skills = mem_vec(&proc.handle, self.souls[0] + df.memory_layout.field_offset(OffsetSection::Soul, "skills"))
                 .iter()
                 .map(|&addr| {
-                Skill::new(df, proc, addr)
+                Skill::new(df, proc, &self.caste, addr)
             }).collect();
         }
-            // TODO: mood skills
 
         pub unsafe fn read_beliefs(&mut self, df: &DFInstance, proc: &Process) {
             self.beliefs = mem_vec(&proc.handle, self.personality_addr + df.memory_layout.field_offset(OffsetSection::Soul, "beliefs"))
@@ -465,6 +643,9 @@ pub mod dwarf {
                         }
                     }
                 }
+                if let Some(extremeness) = self.caste.trait_extremeness(tr.id, val) {
+                    self.trait_extremes.insert(tr.id, extremeness);
+                }
                 self.traits.push((tr.id, tr.name, val));
             }
 
@@ -508,13 +689,24 @@ pub mod dwarf {
                 .iter()
                 .map(|&n| Need::new(df, proc, n))
                 .collect();
+            self.focus = Focus::new(df, proc, self.personality_addr);
         }
 
         pub unsafe fn read_preferences(&mut self, df: &DFInstance, proc: &Process) {
             let prefs: Vec<usize> = mem_vec(&proc.handle,  self.souls[0] + df.memory_layout.field_offset(OffsetSection::Soul, "preferences"));
-            for p in prefs {
-                let pref = Preference::new(df, proc, p);
-                // TODO: add to preferences
+            self.preferences = prefs.iter()
+                .map(|&p| Preference::new(df, proc, p))
+                .filter(|p| p.pref_type != PreferenceType::LikesNone)
+                .collect();
+
+            // liking the outdoors isn't stored with the other preferences
+            let likes_outdoors = read_mem::<i32>(&proc.handle, self.personality_addr + df.memory_layout.field_offset(OffsetSection::Soul, "likes_outdoors"));
+            if likes_outdoors > 0 {
+                self.preferences.push(Preference {
+                    pref_type: PreferenceType::LikeOutdoors,
+                    name: "outdoors".to_string(),
+                    ..Default::default()
+                });
             }
 
         }
@@ -577,7 +769,12 @@ pub mod dwarf {
 
             //check if they've had a mood/artifact if they're not currently in a craft-type mood
             if mood == Mood::None || mood_id > 4 {
-                // todo!();
+                (self.artifact_name, self.translated_artifact_name) = df.read_names(proc, self.addr + df.memory_layout.field_offset(OffsetSection::Dwarf, "artifact_name"));
+                self.artifacts = df.artifacts.iter()
+                    .filter(|a| self.histfig_id >= 0 && a.creator_id == self.histfig_id)
+                    .map(|a| a.id)
+                    .collect();
+                self.had_mood = !self.artifact_name.is_empty() || !self.artifacts.is_empty();
             }
 
             // this feels bad
@@ -589,6 +786,7 @@ pub mod dwarf {
             ) || (mood_id >= 0 && mood_id <= 4) {
                 self.locked_mood = true;
             }
+            self.strange_mood = MoodInfo::new(df, proc, self.addr, mood_id);
             self.mood = mood;
         }
 
@@ -644,6 +842,43 @@ pub mod dwarf {
 
     }
 
+    /// DF's animal training level for creatures that aren't trained or domesticated
+    pub const WILD_UNTAMED: i16 = 9;
+
+    /// What a unit is to the fortress
+    #[derive(Default, Debug, PartialEq, Eq, Clone, Copy, Serialize, Deserialize)]
+    pub enum UnitType {
+        #[default]
+        Citizen,
+        Resident,
+        Visitor,
+        Animal,
+        Hostile,
+        Dead,
+    }
+
+    impl UnitType {
+        pub fn classify(d: &Dwarf, fort_civ_id: i32) -> UnitType {
+            let f1 = |flag: UnitFlag1| d.has_flag1(flag);
+            let f2 = |flag: UnitFlag2| d.has_flag2(flag);
+
+            if d.is_dead() {
+                UnitType::Dead
+            } else if f1(UnitFlag1::Marauder) || f1(UnitFlag1::ActiveInvader) || f1(UnitFlag1::InvaderOrigin) ||
+                f1(UnitFlag1::HiddenAmbusher) || f2(UnitFlag2::Underworld) || f2(UnitFlag2::VisitorUninvited) {
+                UnitType::Hostile
+            } else if (f1(UnitFlag1::Tame) && d.animal_type != WILD_UNTAMED) || d.pet_owner_id >= 0 || d.civ_id < 0 {
+                UnitType::Animal
+            } else if f1(UnitFlag1::Merchant) || f1(UnitFlag1::Diplomat) || f2(UnitFlag2::Visitor) {
+                UnitType::Visitor
+            } else if f2(UnitFlag2::Resident) || d.civ_id != fort_civ_id {
+                UnitType::Resident
+            } else {
+                UnitType::Citizen
+            }
+        }
+    }
+
     #[derive(Default, Debug, PartialEq, Clone, Serialize, Deserialize)]
     pub enum Mood {
         #[default]
@@ -713,4 +948,71 @@ pub mod dwarf {
         name: String,
         enabled: bool,
     }
+
+    #[cfg(test)]
+    mod tests {
+        use super::*;
+
+        const FORT_CIV: i32 = 10;
+
+        fn unit(flags1: &[UnitFlag1], flags2: &[UnitFlag2]) -> Dwarf {
+            let mut d = Dwarf {
+                civ_id: FORT_CIV,
+                animal_type: WILD_UNTAMED,
+                pet_owner_id: -1,
+                ..Default::default()
+            };
+            flags1.iter().for_each(|&f| d.set_flag1(f));
+            flags2.iter().for_each(|&f| d.set_flag2(f));
+            d
+        }
+
+        #[test]
+        fn set_flags() {
+            let mut d = Dwarf::default();
+            d.set_flag1(UnitFlag1::Dead);
+            d.set_flag1(UnitFlag1::Merchant);
+            d.set_flag2(UnitFlag2::Resident);
+            assert!(d.has_flag1(UnitFlag1::Dead) && d.has_flag1(UnitFlag1::Merchant));
+            assert!(!d.has_flag1(UnitFlag1::Marauder));
+            assert!(d.has_flag2(UnitFlag2::Resident));
+            assert_eq!(d.flags1, (1 << UnitFlag1::Dead as u32) | (1 << UnitFlag1::Merchant as u32));
+        }
+
+        #[test]
+        fn classify_fortress_units() {
+            assert_eq!(UnitType::classify(&unit(&[], &[]), FORT_CIV), UnitType::Citizen);
+            assert_eq!(UnitType::classify(&unit(&[], &[UnitFlag2::Resident]), FORT_CIV), UnitType::Resident);
+            assert_eq!(UnitType::classify(&unit(&[UnitFlag1::Merchant], &[]), FORT_CIV), UnitType::Visitor);
+            assert_eq!(UnitType::classify(&unit(&[UnitFlag1::Marauder], &[]), FORT_CIV), UnitType::Hostile);
+            assert_eq!(UnitType::classify(&unit(&[UnitFlag1::Dead, UnitFlag1::Marauder], &[]), FORT_CIV), UnitType::Dead);
+
+            let mut other_civ = unit(&[], &[]);
+            other_civ.civ_id = FORT_CIV + 1;
+            assert_eq!(UnitType::classify(&other_civ, FORT_CIV), UnitType::Resident);
+        }
+
+        #[test]
+        fn classify_animals() {
+            let mut tame = unit(&[UnitFlag1::Tame], &[]);
+            tame.animal_type = 7;
+            assert_eq!(UnitType::classify(&tame, FORT_CIV), UnitType::Animal);
+
+            let mut pet = unit(&[], &[]);
+            pet.pet_owner_id = 3;
+            assert_eq!(UnitType::classify(&pet, FORT_CIV), UnitType::Animal);
+
+            let mut wild = unit(&[], &[]);
+            wild.civ_id = -1;
+            assert_eq!(UnitType::classify(&wild, FORT_CIV), UnitType::Animal);
+        }
+
+        #[test]
+        fn training_level_alone_is_not_an_animal() {
+            // the training level isn't meaningful for citizens, only tame units count as trained animals
+            let mut citizen = unit(&[], &[]);
+            citizen.animal_type = 0;
+            assert_eq!(UnitType::classify(&citizen, FORT_CIV), UnitType::Citizen);
+        }
+    }
 }
\ No newline at end of file
@@ -29,11 +29,9 @@ impl FlagArray {
             for i in 0..size_in_bytes {
                 let byte = read_mem::<u8>(&proc.handle, flags_addr);
                 if byte > 0 {
-                    for p in (0..=7).rev() {
-                        let mut iter = 128;
-                        while iter > 0 {
+                    for p in 0..8 {
+                        if byte & (1 << p) != 0 {
                             let _ = flags.set(i * 8 + p, true);
-                            iter /= 2;
                         }
                     }
                 }
@@ -111,6 +109,14 @@ impl BitArray {
     pub fn size(&self) -> usize {
         self.size
     }
+
+    /// Extends the array to hold at least `size` bits. New bits are unset.
+    pub fn grow(&mut self, size: usize) {
+        if size > self.size {
+            self.data.resize(size.div_ceil(8), 0);
+            self.size = size;
+        }
+    }
 }
 
 impl Clone for BitArray {
@@ -0,0 +1,392 @@
+use serde::{Deserialize, Serialize};
+
+use crate::DFInstance;
+use crate::caste::caste::Caste;
+use crate::data::memorylayout::OffsetSection;
+use crate::util::memory::read_mem_as_string;
+use crate::win::memory::memory::{mem_vec, read_mem};
+use crate::win::process::Process;
+
+// health_info category ids
+const HI_TREATMENT: i32 = 0;
+const HI_BLEEDING: i32 = 1;
+const HI_PAIN: i32 = 7;
+const HI_NERVES: i32 = 20;
+const HI_ARTERIES: i32 = 21;
+const HI_FRACTURES: i32 = 22;
+const HI_TENDONS: i32 = 23;
+const HI_LIGAMENTS: i32 = 24;
+const HI_NEEDS_SETTING: i32 = 25;
+const HI_INFECTION: i32 = 29;
+const HI_LACERATIONS: i32 = 30;
+const HI_SEVERED: i32 = 31;
+const HI_IMMOBILIZATION: i32 = 34;
+const HI_DRESSING: i32 = 35;
+const HI_CLEANING: i32 = 36;
+const HI_SURGERY: i32 = 37;
+const HI_SUTURES: i32 = 38;
+const HI_TRACTION: i32 = 39;
+const HI_CRUTCH: i32 = 40;
+const HI_GUTTED: i32 = 16;
+const HI_OTHER_DAMAGE: i32 = 41;
+
+const HEAVY_BLEEDING: i32 = 10;
+const EXTREME_PAIN: i32 = 100;
+const MODERATE_PAIN: i32 = 50;
+
+/// Treatment requests in the unit's health info flags
+#[derive(Debug, Clone, Copy)]
+enum HealthFlag {
+    RequestDiagnosis = 0,
+    NeedsRecovery = 1,
+    RequestImmobilize = 3,
+    RequestDressing = 4,
+    RequestCleaning = 5,
+    RequestSurgery = 6,
+    RequestSuture = 7,
+    RequestSetting = 8,
+    RequestTraction = 9,
+    RequestCrutch = 10,
+}
+
+/// Flags on the wound itself
+#[derive(Debug, Clone, Copy)]
+enum WoundFlag {
+    SeveredPart = 0,
+    MortalWound = 1,
+    Diagnosed = 3,
+    Sutured = 4,
+    Infection = 5,
+}
+
+/// Damage flags on each wounded body part layer, DF's wound_damage_flags1
+#[derive(Debug, Clone, Copy)]
+enum WoundDamageFlag {
+    Cut = 0,
+    Smashed = 1,
+    TendonBruised = 4,
+    TendonStrained = 5,
+    TendonTorn = 6,
+    LigamentBruised = 7,
+    LigamentSprained = 8,
+    LigamentTorn = 9,
+    MotorNerveSevered = 10,
+    SensoryNerveSevered = 11,
+    SmashedApart = 13,
+    MajorArtery = 14,
+    GutsSpilled = 15,
+    Broken = 19,
+    Gouged = 21,
+    CompoundFracture = 28,
+    Artery = 30,
+    OverlappingFracture = 31,
+}
+
+/// One entry in a health_info category, e.g. Bleeding / "Heavy Bleeding" / "B!"
+#[derive(Default, Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct HealthStatus {
+    pub category_id: i32,
+    pub category: String,
+    pub description: String,
+    pub symbol: String,
+    pub color: String,
+}
+
+impl HealthStatus {
+    /// `severity` is the index into the category's descriptions, 0 being the worst
+    fn new(df: &DFInstance, category_id: i32, severity: usize) -> Option<HealthStatus> {
+        let info = df.game_data.health_info.iter().find(|h| h.id == category_id)?;
+        let desc = info.descriptions.get(severity)?;
+        Some(HealthStatus {
+            category_id,
+            category: info.name.clone(),
+            description: desc.desc.clone(),
+            symbol: desc.symbol.clone(),
+            color: info.color.clone(),
+        })
+    }
+}
+
+#[derive(Default, Debug, Clone, Serialize, Deserialize)]
+pub struct WoundPart {
+    pub body_part_id: i16,
+    pub body_part: String,
+    pub layer_id: i16,
+    pub layer: String,
+    pub bleeding: i32,
+    pub pain: i32,
+    pub penetration: i16,
+    pub max_penetration: i16,
+    pub effects: Vec<i16>,
+    pub statuses: Vec<HealthStatus>,
+}
+
+#[derive(Default, Debug, Clone, Serialize, Deserialize)]
+pub struct Wound {
+    pub addr: usize,
+    pub parts: Vec<WoundPart>,
+    pub severed: bool,
+    pub mortal: bool,
+    pub diagnosed: bool,
+    pub sutured: bool,
+    pub infected: bool,
+}
+
+impl Wound {
+    pub unsafe fn new(df: &DFInstance, proc: &Process, caste: &Caste, addr: usize) -> Wound {
+        let general_flags = read_mem::<u32>(&proc.handle, addr + df.memory_layout.field_offset(OffsetSection::UnitWound, "general_flags"));
+        let has = |f: WoundFlag| general_flags & (1 << f as u32) != 0;
+
+        let severed = has(WoundFlag::SeveredPart);
+        Wound {
+            addr,
+            severed,
+            mortal:    has(WoundFlag::MortalWound),
+            diagnosed: has(WoundFlag::Diagnosed),
+            sutured:   has(WoundFlag::Sutured),
+            infected:  has(WoundFlag::Infection),
+            parts: mem_vec::<usize>(&proc.handle, addr + df.memory_layout.field_offset(OffsetSection::UnitWound, "parts"))
+                .iter()
+                .map(|&p| WoundPart::new(df, proc, caste, p, severed))
+                .collect(),
+        }
+    }
+}
+
+impl WoundPart {
+    /// Severing is a flag on the whole wound, so it's passed down to mark the part
+    pub unsafe fn new(df: &DFInstance, proc: &Process, caste: &Caste, addr: usize, severed: bool) -> WoundPart {
+        let mut part = WoundPart {
+            body_part_id:    read_mem::<i16>(&proc.handle, addr + df.memory_layout.field_offset(OffsetSection::UnitWound, "id")),
+            layer_id:        read_mem::<i16>(&proc.handle, addr + df.memory_layout.field_offset(OffsetSection::UnitWound, "layer")),
+            bleeding:        read_mem::<i32>(&proc.handle, addr + df.memory_layout.field_offset(OffsetSection::UnitWound, "bleeding")),
+            pain:            read_mem::<i32>(&proc.handle, addr + df.memory_layout.field_offset(OffsetSection::UnitWound, "pain")),
+            penetration:     read_mem::<i16>(&proc.handle, addr + df.memory_layout.field_offset(OffsetSection::UnitWound, "cur_pen")),
+            max_penetration: read_mem::<i16>(&proc.handle, addr + df.memory_layout.field_offset(OffsetSection::UnitWound, "max_pen")),
+            effects:         mem_vec::<i16>(&proc.handle, addr + df.memory_layout.field_offset(OffsetSection::UnitWound, "effects_vector")),
+            ..Default::default()
+        };
+        part.read_body_part(df, proc, caste);
+
+        let flags1 = read_mem::<u32>(&proc.handle, addr + df.memory_layout.field_offset(OffsetSection::UnitWound, "flags1"));
+        part.read_statuses(df, flags1, severed);
+        part
+    }
+
+    unsafe fn read_body_part(&mut self, df: &DFInstance, proc: &Process, caste: &Caste) {
+        self.body_part = caste.body_part_name(df, proc, self.body_part_id);
+
+        let bp_addr = match caste.body_parts_addr.get(self.body_part_id as usize) {
+            Some(&a) if self.body_part_id >= 0 => a,
+            _ => return,
+        };
+        let layers = mem_vec::<usize>(&proc.handle, bp_addr + df.memory_layout.field_offset(OffsetSection::Health, "layers_vector"));
+        if let Some(&layer) = layers.get(self.layer_id as usize) {
+            self.layer = read_mem_as_string(proc, layer).to_lowercase();
+        }
+    }
+
+    fn read_statuses(&mut self, df: &DFInstance, flags1: u32, severed: bool) {
+        let apart = self.max_penetration > 0 && self.penetration >= self.max_penetration;
+        let mut found = damage_statuses(flags1, apart, severed);
+
+        // bruises, burns, frostbite and so on share the order of the health_info descriptions
+        for &effect in &self.effects {
+            if effect >= 0 {
+                found.push((HI_OTHER_DAMAGE, effect as usize));
+            }
+        }
+
+        self.statuses = found.iter()
+            .filter_map(|&(category, severity)| HealthStatus::new(df, category, severity))
+            .collect();
+    }
+}
+
+/// The (health_info category, severity index) pairs for a layer's damage flags, most severe first in each category.
+/// `apart` is whether the damage went all the way through the layer.
+fn damage_statuses(flags1: u32, apart: bool, severed: bool) -> Vec<(i32, usize)> {
+    let has = |f: WoundDamageFlag| flags1 & (1 << f as u32) != 0;
+    let mut found: Vec<(i32, usize)> = vec![];
+
+    if severed { found.push((HI_SEVERED, 0)); }
+    if has(WoundDamageFlag::GutsSpilled) { found.push((HI_GUTTED, 0)); }
+
+    if has(WoundDamageFlag::OverlappingFracture) { found.push((HI_FRACTURES, 0)); }
+    else if has(WoundDamageFlag::CompoundFracture) { found.push((HI_FRACTURES, 1)); }
+    else if has(WoundDamageFlag::Broken) { found.push((HI_FRACTURES, if apart { 2 } else { 3 })); }
+
+    if has(WoundDamageFlag::MajorArtery) { found.push((HI_ARTERIES, 0)); }
+    else if has(WoundDamageFlag::Artery) { found.push((HI_ARTERIES, 1)); }
+
+    if has(WoundDamageFlag::MotorNerveSevered) { found.push((HI_NERVES, 0)); }
+    if has(WoundDamageFlag::SensoryNerveSevered) { found.push((HI_NERVES, 1)); }
+
+    if has(WoundDamageFlag::TendonTorn) { found.push((HI_TENDONS, 0)); }
+    else if has(WoundDamageFlag::TendonStrained) { found.push((HI_TENDONS, 1)); }
+    else if has(WoundDamageFlag::TendonBruised) { found.push((HI_TENDONS, 2)); }
+
+    if has(WoundDamageFlag::LigamentTorn) { found.push((HI_LIGAMENTS, 0)); }
+    else if has(WoundDamageFlag::LigamentSprained) { found.push((HI_LIGAMENTS, 1)); }
+    else if has(WoundDamageFlag::LigamentBruised) { found.push((HI_LIGAMENTS, 2)); }
+
+    if has(WoundDamageFlag::SmashedApart) { found.push((HI_LACERATIONS, 0)); }
+    else if has(WoundDamageFlag::Smashed) { found.push((HI_LACERATIONS, 1)); }
+    if has(WoundDamageFlag::Cut) { found.push((HI_LACERATIONS, if apart { 4 } else { 5 })); }
+    if has(WoundDamageFlag::Gouged) { found.push((HI_LACERATIONS, if apart { 8 } else { 9 })); }
+    found
+}
+
+#[derive(Default, Debug, Clone, Serialize, Deserialize)]
+pub struct Health {
+    pub wounds: Vec<Wound>,
+    pub bleeding: i32,
+    pub pain: i32,
+    pub is_infected: bool,
+    pub needs_diagnosis: bool,
+    pub needs_recovery: bool,
+    pub needs_surgery: bool,
+    /// the worst status in each health_info category, for the health column
+    pub statuses: Vec<HealthStatus>,
+    /// every treatment the unit is waiting on
+    pub treatments: Vec<HealthStatus>,
+}
+
+impl Health {
+    pub unsafe fn new(df: &DFInstance, proc: &Process, unit_addr: usize, caste: &Caste) -> Health {
+        let mut health = Health {
+            wounds: mem_vec::<usize>(&proc.handle, unit_addr + df.memory_layout.field_offset(OffsetSection::Dwarf, "wounds_vector"))
+                .iter()
+                .map(|&w| Wound::new(df, proc, caste, w))
+                .collect(),
+            ..Default::default()
+        };
+
+        for wound in &health.wounds {
+            health.is_infected |= wound.infected;
+            for part in &wound.parts {
+                health.bleeding += part.bleeding;
+                health.pain += part.pain;
+            }
+        }
+
+        let health_info_addr = read_mem::<usize>(&proc.handle, unit_addr + df.memory_layout.field_offset(OffsetSection::Dwarf, "unit_health_info"));
+        if health_info_addr != 0 {
+            let flags = read_mem::<u32>(&proc.handle, health_info_addr + df.memory_layout.field_offset(OffsetSection::Health, "flags"));
+            health.read_treatments(df, flags);
+        }
+
+        health.read_statuses(df);
+        health
+    }
+
+    fn read_treatments(&mut self, df: &DFInstance, flags: u32) {
+        let has = |f: HealthFlag| flags & (1 << f as u32) != 0;
+        self.needs_diagnosis = has(HealthFlag::RequestDiagnosis);
+        self.needs_recovery = has(HealthFlag::NeedsRecovery);
+        self.needs_surgery = has(HealthFlag::RequestSurgery);
+
+        let treatments = [
+            (HealthFlag::NeedsRecovery, HI_TREATMENT, 0),
+            (HealthFlag::RequestDiagnosis, HI_TREATMENT, 1),
+            (HealthFlag::RequestImmobilize, HI_IMMOBILIZATION, 0),
+            (HealthFlag::RequestDressing, HI_DRESSING, 0),
+            (HealthFlag::RequestCleaning, HI_CLEANING, 0),
+            (HealthFlag::RequestSurgery, HI_SURGERY, 0),
+            (HealthFlag::RequestSuture, HI_SUTURES, 0),
+            (HealthFlag::RequestSetting, HI_NEEDS_SETTING, 0),
+            (HealthFlag::RequestTraction, HI_TRACTION, 0),
+            (HealthFlag::RequestCrutch, HI_CRUTCH, 0),
+        ];
+        self.treatments = treatments.iter()
+            .filter(|(flag, _, _)| has(*flag))
+            .filter_map(|&(_, category, severity)| HealthStatus::new(df, category, severity))
+            .collect();
+    }
+
+    fn read_statuses(&mut self, df: &DFInstance) {
+        let mut statuses: Vec<HealthStatus> = self.treatments.clone();
+
+        if self.bleeding >= HEAVY_BLEEDING {
+            statuses.extend(HealthStatus::new(df, HI_BLEEDING, 0));
+        } else if self.bleeding > 0 {
+            statuses.extend(HealthStatus::new(df, HI_BLEEDING, 1));
+        }
+
+        if self.pain >= EXTREME_PAIN {
+            statuses.extend(HealthStatus::new(df, HI_PAIN, 0));
+        } else if self.pain >= MODERATE_PAIN {
+            statuses.extend(HealthStatus::new(df, HI_PAIN, 1));
+        } else if self.pain > 0 {
+            statuses.extend(HealthStatus::new(df, HI_PAIN, 2));
+        }
+
+        if self.is_infected {
+            statuses.extend(HealthStatus::new(df, HI_INFECTION, 0));
+        }
+
+        statuses.extend(self.wounds.iter().flat_map(|w| w.parts.iter()).flat_map(|p| p.statuses.iter().cloned()));
+        self.statuses = HealthStatus::worst_per_category(df, statuses);
+    }
+}
+
+impl HealthStatus {
+    /// Keeps the most severe status of each category
+    fn worst_per_category(df: &DFInstance, statuses: Vec<HealthStatus>) -> Vec<HealthStatus> {
+        let severity = |s: &HealthStatus| {
+            df.game_data.health_info.iter()
+                .find(|h| h.id == s.category_id)
+                .and_then(|h| h.descriptions.iter().position(|d| d.desc == s.description))
+                .unwrap_or(usize::MAX)
+        };
+
+        let mut worst: Vec<HealthStatus> = vec![];
+        for s in statuses {
+            match worst.iter_mut().find(|w| w.category_id == s.category_id) {
+                Some(w) if severity(&s) < severity(w) => *w = s,
+                Some(_) => (),
+                None => worst.push(s),
+            }
+        }
+        worst
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn flags(flags: &[WoundDamageFlag]) -> u32 {
+        flags.iter().fold(0, |f, &flag| f | (1 << flag as u32))
+    }
+
+    #[test]
+    fn fractures_by_severity() {
+        assert_eq!(damage_statuses(flags(&[WoundDamageFlag::Broken]), false, false), vec![(HI_FRACTURES, 3)]);
+        assert_eq!(damage_statuses(flags(&[WoundDamageFlag::Broken]), true, false), vec![(HI_FRACTURES, 2)]);
+        assert_eq!(damage_statuses(flags(&[WoundDamageFlag::Broken, WoundDamageFlag::CompoundFracture]), true, false), vec![(HI_FRACTURES, 1)]);
+        assert_eq!(damage_statuses(flags(&[WoundDamageFlag::Broken, WoundDamageFlag::OverlappingFracture]), true, false), vec![(HI_FRACTURES, 0)]);
+    }
+
+    #[test]
+    fn arteries_and_severing() {
+        assert_eq!(damage_statuses(flags(&[WoundDamageFlag::Artery]), false, false), vec![(HI_ARTERIES, 1)]);
+        assert_eq!(damage_statuses(flags(&[WoundDamageFlag::Artery, WoundDamageFlag::MajorArtery]), false, false), vec![(HI_ARTERIES, 0)]);
+        assert_eq!(damage_statuses(0, false, true), vec![(HI_SEVERED, 0)]);
+    }
+
+    #[test]
+    fn scar_and_joint_bits_are_not_damage() {
+        // scar_blunt_shake1, joint_bend1, scar_joint_bend1 and joint_bend2
+        let scars = (1 << 23) | (1 << 25) | (1 << 26) | (1 << 27);
+        assert!(damage_statuses(scars, true, false).is_empty());
+    }
+
+    #[test]
+    fn lacerations() {
+        assert_eq!(damage_statuses(flags(&[WoundDamageFlag::Cut]), false, false), vec![(HI_LACERATIONS, 5)]);
+        assert_eq!(damage_statuses(flags(&[WoundDamageFlag::Cut]), true, false), vec![(HI_LACERATIONS, 4)]);
+        assert_eq!(damage_statuses(flags(&[WoundDamageFlag::Smashed, WoundDamageFlag::SmashedApart]), true, false), vec![(HI_LACERATIONS, 0)]);
+        assert_eq!(damage_statuses(flags(&[WoundDamageFlag::Gouged]), false, false), vec![(HI_LACERATIONS, 9)]);
+    }
+}
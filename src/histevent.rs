@@ -0,0 +1,298 @@
+use serde::{Deserialize, Serialize};
+
+use crate::DFInstance;
+use crate::data::memorylayout::OffsetSection;
+use crate::win::memory::memory::read_mem;
+use crate::win::process::Process;
+
+/// histfig_entity_link_type values used by entity link events
+const ENTITY_LINK_MEMBER: i32 = 0;
+const ENTITY_LINK_POSITION: i32 = 10;
+
+/// histfig_hf_link_type names, indexed by link type
+const HF_LINK_NAMES: [&str; 16] = [
+    "mother", "father", "spouse", "child", "deity", "lover", "prisoner", "imprisoner",
+    "master", "apprentice", "companion", "former master", "former apprentice", "pet owner",
+    "former spouse", "deceased spouse",
+];
+
+/// hf states used by change state events
+const HF_STATE_NAMES: [&str; 5] = ["visiting", "settled", "wandering", "a refugee", "a snatcher"];
+
+#[derive(Default, Debug, Clone, Serialize, Deserialize)]
+pub struct HistEvent {
+    pub addr: usize,
+    pub id: i32,
+    pub year: i32,
+    pub event_type: HistEventType,
+    /// the raw type id, kept for the types not decoded by `HistEventType`
+    pub type_id: i32,
+    /// every historical figure involved in the event
+    pub histfig_ids: Vec<i32>,
+    pub detail: HistEventDetail,
+    /// filled in by `describe`
+    pub description: String,
+    /// `description` with the names translated to English
+    pub translated_description: String,
+}
+
+/// The parts of an event that depend on its type
+#[derive(Default, Debug, Clone, Serialize, Deserialize)]
+pub enum HistEventDetail {
+    #[default]
+    None,
+    Died {
+        victim_id: i32,
+        slayer_id: i32,
+    },
+    ArtifactCreated {
+        artifact_id: i32,
+        creator_id: i32,
+    },
+    EntityLink {
+        histfig_id: i32,
+        entity_id: i32,
+        link_type: i32,
+        position_id: i32,
+        added: bool,
+    },
+    HfLink {
+        histfig_id: i32,
+        target_id: i32,
+        link_type: i32,
+        added: bool,
+    },
+    ChangeState {
+        histfig_id: i32,
+        state: i32,
+    },
+    ChangeJob {
+        histfig_id: i32,
+    },
+}
+
+impl HistEvent {
+    pub unsafe fn new(df: &DFInstance, proc: &Process, addr: usize) -> HistEvent {
+        // the event type is returned by the first vtable method
+        let vtable_addr = read_mem::<usize>(&proc.handle, addr);
+        let vtable = read_mem::<usize>(&proc.handle, vtable_addr);
+        let type_id = read_mem::<i32>(&proc.handle, vtable + 0x1);
+        let event_type = HistEventType::from(type_id);
+
+        let offset = |field: &str| addr + df.memory_layout.field_offset(OffsetSection::HistEvent, field);
+        let mut e = HistEvent {
+            addr,
+            id:   read_mem::<i32>(&proc.handle, offset("id")),
+            year: read_mem::<i32>(&proc.handle, offset("event_year")),
+            event_type,
+            type_id,
+            ..Default::default()
+        };
+
+        e.detail = match event_type {
+            HistEventType::HistFigureDied => HistEventDetail::Died {
+                victim_id: read_mem::<i32>(&proc.handle, offset("killed_hist_id")),
+                slayer_id: read_mem::<i32>(&proc.handle, offset("slayer_hist_id")),
+            },
+            HistEventType::ArtifactCreated => HistEventDetail::ArtifactCreated {
+                artifact_id: read_mem::<i32>(&proc.handle, offset("artifact_id")),
+                creator_id:  read_mem::<i32>(&proc.handle, offset("artifact_creator_hist_id")),
+            },
+            HistEventType::AddHfEntityLink | HistEventType::RemoveHfEntityLink => HistEventDetail::EntityLink {
+                histfig_id:  read_mem::<i32>(&proc.handle, offset("link_hist_id")),
+                entity_id:   read_mem::<i32>(&proc.handle, offset("link_entity_id")),
+                link_type:   read_mem::<i32>(&proc.handle, offset("link_type")),
+                position_id: read_mem::<i32>(&proc.handle, offset("link_position_id")),
+                added:       event_type == HistEventType::AddHfEntityLink,
+            },
+            HistEventType::AddHfHfLink | HistEventType::RemoveHfHfLink => HistEventDetail::HfLink {
+                histfig_id: read_mem::<i32>(&proc.handle, offset("hf_link_hist_id")),
+                target_id:  read_mem::<i32>(&proc.handle, offset("hf_link_target_id")),
+                link_type:  read_mem::<i32>(&proc.handle, offset("hf_link_type")),
+                added:      event_type == HistEventType::AddHfHfLink,
+            },
+            HistEventType::ChangeHfState => HistEventDetail::ChangeState {
+                histfig_id: read_mem::<i32>(&proc.handle, offset("state_hist_id")),
+                state:      read_mem::<i32>(&proc.handle, offset("state")),
+            },
+            HistEventType::ChangeHfJob => HistEventDetail::ChangeJob {
+                histfig_id: read_mem::<i32>(&proc.handle, offset("job_hist_id")),
+            },
+            _ => HistEventDetail::None,
+        };
+
+        e.histfig_ids = match e.detail {
+            HistEventDetail::Died { victim_id, slayer_id } => vec![victim_id, slayer_id],
+            HistEventDetail::ArtifactCreated { creator_id, .. } => vec![creator_id],
+            HistEventDetail::EntityLink { histfig_id, .. } => vec![histfig_id],
+            HistEventDetail::HfLink { histfig_id, target_id, .. } => vec![histfig_id, target_id],
+            HistEventDetail::ChangeState { histfig_id, .. } => vec![histfig_id],
+            HistEventDetail::ChangeJob { histfig_id } => vec![histfig_id],
+            HistEventDetail::None => vec![],
+        };
+        e.histfig_ids.retain(|&id| id >= 0);
+        e
+    }
+
+    pub fn involves(&self, histfig_id: i32) -> bool {
+        self.histfig_ids.contains(&histfig_id)
+    }
+
+    /// Fills in a sentence describing the event, e.g. "In 105, Urist Lolokzefon was slain by Snodub",
+    /// once with native names and once with English ones
+    pub unsafe fn describe(&mut self, df: &DFInstance, proc: &Process) {
+        let name = |id: i32, translated: bool| {
+            let (native, english) = df.get_histfig_names(proc, id);
+            match if translated { english } else { native } {
+                n if n.is_empty() => format!("someone ({id})"),
+                n => n,
+            }
+        };
+        self.description = self.sentence(df, &|id| name(id, false));
+        self.translated_description = self.sentence(df, &|id| name(id, true));
+    }
+
+    fn sentence(&self, df: &DFInstance, name: &dyn Fn(i32) -> String) -> String {
+        let what = match &self.detail {
+            HistEventDetail::Died { victim_id, slayer_id } => match *slayer_id >= 0 {
+                true => format!("{} was slain by {}", name(*victim_id), name(*slayer_id)),
+                false => format!("{} died", name(*victim_id)),
+            },
+            HistEventDetail::ArtifactCreated { artifact_id, creator_id } => {
+                format!("{} created artifact {}", name(*creator_id), artifact_id)
+            },
+            HistEventDetail::EntityLink { histfig_id, link_type, position_id, added, .. } => {
+                let who = name(*histfig_id);
+                match (*link_type, *added) {
+                    (ENTITY_LINK_POSITION, true) => format!("{who} became {}", self.position_name(df, *position_id)),
+                    (ENTITY_LINK_POSITION, false) => format!("{who} stopped being {}", self.position_name(df, *position_id)),
+                    (ENTITY_LINK_MEMBER, true) => format!("{who} joined an entity"),
+                    (ENTITY_LINK_MEMBER, false) => format!("{who} left an entity"),
+                    (_, true) => format!("{who} was linked to an entity"),
+                    (_, false) => format!("{who} was unlinked from an entity"),
+                }
+            },
+            HistEventDetail::HfLink { histfig_id, target_id, link_type, added } => {
+                let link = HF_LINK_NAMES.get(*link_type as usize).copied().unwrap_or("relation");
+                match *added {
+                    true => format!("{} became the {link} of {}", name(*target_id), name(*histfig_id)),
+                    false => format!("{} stopped being the {link} of {}", name(*target_id), name(*histfig_id)),
+                }
+            },
+            HistEventDetail::ChangeState { histfig_id, state } => {
+                let state = HF_STATE_NAMES.get(*state as usize).copied().unwrap_or("on the move");
+                format!("{} was {state}", name(*histfig_id))
+            },
+            HistEventDetail::ChangeJob { histfig_id } => format!("{} changed jobs", name(*histfig_id)),
+            HistEventDetail::None => format!("{:?}", self.event_type),
+        };
+        format!("In {}, {what}", self.year)
+    }
+
+    fn position_name(&self, df: &DFInstance, position_id: i32) -> String {
+        match df.positions.get(&position_id) {
+            Some(p) => format!("the {}", p.name),
+            None => "a position holder".to_string(),
+        }
+    }
+}
+
+/// Events returned per page when no limit is given, and the most a page can hold
+const DEFAULT_EVENT_LIMIT: usize = 100;
+const MAX_EVENT_LIMIT: usize = 1000;
+
+/// Filters for looking up events, any that are `None` match everything.
+/// Results are paged with `offset` and `limit`, since the whole history can be very large.
+#[derive(Default, Debug, Clone, Deserialize)]
+pub struct EventQuery {
+    pub histfig_id: Option<i32>,
+    pub from_year: Option<i32>,
+    pub to_year: Option<i32>,
+    pub event_type: Option<HistEventType>,
+    pub offset: Option<usize>,
+    pub limit: Option<usize>,
+}
+
+impl EventQuery {
+    pub fn matches(&self, e: &HistEvent) -> bool {
+        self.histfig_id.is_none_or(|id| e.involves(id)) &&
+        self.from_year.is_none_or(|y| e.year >= y) &&
+        self.to_year.is_none_or(|y| e.year <= y) &&
+        self.event_type.is_none_or(|t| e.event_type == t)
+    }
+
+    pub fn limit(&self) -> usize {
+        self.limit.unwrap_or(DEFAULT_EVENT_LIMIT).min(MAX_EVENT_LIMIT)
+    }
+}
+
+/// The history event types we know about, the rest are `Other`
+#[derive(Default, Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum HistEventType {
+    #[default]
+    Other = -1,
+    WarAttackedSite = 0,
+    WarDestroyedSite = 1,
+    CreatedSite = 2,
+    HistFigureDied = 3,
+    AddHfEntityLink = 4,
+    RemoveHfEntityLink = 5,
+    ArtifactCreated = 18,
+    ArtifactLost = 19,
+    ArtifactFound = 20,
+    AddHfSiteLink = 32,
+    RemoveHfSiteLink = 33,
+    AddHfHfLink = 34,
+    RemoveHfHfLink = 35,
+    MasterpieceCreatedItem = 39,
+    ChangeHfState = 45,
+    ChangeHfJob = 46,
+    HistFigureWounded = 57,
+    ChangeCreatureType = 66,
+    HistFigureRevived = 67,
+    ArtifactStored = 75,
+    Performance = 82,
+    KnowledgeDiscovered = 86,
+    PoeticFormCreated = 91,
+    MusicalFormCreated = 92,
+    DanceFormCreated = 93,
+    WrittenContentComposed = 94,
+    ChangeHfMood = 95,
+    ArtifactGiven = 97,
+}
+
+impl From<i32> for HistEventType {
+    fn from(value: i32) -> Self {
+        match value {
+            0 => HistEventType::WarAttackedSite,
+            1 => HistEventType::WarDestroyedSite,
+            2 => HistEventType::CreatedSite,
+            3 => HistEventType::HistFigureDied,
+            4 => HistEventType::AddHfEntityLink,
+            5 => HistEventType::RemoveHfEntityLink,
+            18 => HistEventType::ArtifactCreated,
+            19 => HistEventType::ArtifactLost,
+            20 => HistEventType::ArtifactFound,
+            32 => HistEventType::AddHfSiteLink,
+            33 => HistEventType::RemoveHfSiteLink,
+            34 => HistEventType::AddHfHfLink,
+            35 => HistEventType::RemoveHfHfLink,
+            39 => HistEventType::MasterpieceCreatedItem,
+            45 => HistEventType::ChangeHfState,
+            46 => HistEventType::ChangeHfJob,
+            57 => HistEventType::HistFigureWounded,
+            66 => HistEventType::ChangeCreatureType,
+            67 => HistEventType::HistFigureRevived,
+            75 => HistEventType::ArtifactStored,
+            82 => HistEventType::Performance,
+            86 => HistEventType::KnowledgeDiscovered,
+            91 => HistEventType::PoeticFormCreated,
+            92 => HistEventType::MusicalFormCreated,
+            93 => HistEventType::DanceFormCreated,
+            94 => HistEventType::WrittenContentComposed,
+            95 => HistEventType::ChangeHfMood,
+            97 => HistEventType::ArtifactGiven,
+            _ => HistEventType::Other,
+        }
+    }
+}
@@ -1,6 +1,13 @@
+use std::cmp::Reverse;
+
 use serde::{Deserialize, Serialize};
 
-use crate::{data::memorylayout::OffsetSection, util::memory::read_mem_as_string, win::{memory::memory::read_mem, process::Process}, DFInstance};
+use crate::histevent::HistEventDetail;
+use crate::knowledge::KnownTopic;
+use crate::language::Name;
+use crate::util::capitalize_each;
+use crate::relationship::Relation;
+use crate::{data::memorylayout::OffsetSection, win::{memory::memory::{mem_vec, read_mem}, process::Process}, DFInstance};
 
 #[derive(Debug, Default, Serialize, Deserialize, Clone)]
 pub struct FakeIdentity {
@@ -9,6 +16,8 @@ pub struct FakeIdentity {
     fake_name_addr: usize,
     pub fake_name: String,
     pub fake_nickname: String,
+    pub fake_last_name: String,
+    pub fake_translated_last_name: String,
     pub fake_birth_year: i32,
     pub fake_birth_time: i32,
 }
@@ -16,33 +25,146 @@ pub struct FakeIdentity {
 #[derive(Debug, Default, Serialize, Deserialize, Clone)]
 pub struct HistoricalFigure {
     pub id : i32,
+    pub name: String,
+    pub translated_name: String,
+    pub race_id: i32,
     pub fig_info_addr: usize,
     pub nick_addrs: Vec<usize>,
     pub reputation: usize,
 
     pub fake_identity: FakeIdentity,
+    /// kills of other historical figures
+    pub kills: Vec<Kill>,
+    /// kills of unnamed creatures, grouped by race
+    pub kill_counts: Vec<KillCount>,
     pub total_kills_other: i32,
     pub has_fake_identity: bool,
+    pub known_topics: Vec<KnownTopic>,
+    /// family, deities, masters and apprentices
+    pub links: Vec<Relation>,
+    /// friends, acquaintances and grudges
+    pub relationships: Vec<Relation>,
+}
+
+/// A historical figure this one has killed
+#[derive(Debug, Default, Serialize, Deserialize, Clone)]
+pub struct Kill {
+    pub histfig_id: i32,
+    pub name: String,
+    pub translated_name: String,
+    pub race_id: i32,
+    pub race: String,
+    pub event_id: i32,
+    pub year: i32,
+}
+
+#[derive(Debug, Default, Serialize, Deserialize, Clone)]
+pub struct KillCount {
+    pub race_id: i32,
+    pub race: String,
+    pub count: i32,
+    pub undead: bool,
 }
 
 impl HistoricalFigure {
     pub unsafe fn new(df: &DFInstance, proc: &Process, id: i32) -> HistoricalFigure {
         let hf_addr = df.historical_figures.get(&id).unwrap();
-        let fig_info_addr = hf_addr + df.memory_layout.field_offset(OffsetSection::HistFigure, "hist_fig_info");
+        let fig_info_addr = HistoricalFigure::info_addr(df, proc, *hf_addr);
 
+        let (name, translated_name) = df.read_names(proc, hf_addr + df.memory_layout.field_offset(OffsetSection::HistFigure, "hist_name"));
         let mut hf: HistoricalFigure = HistoricalFigure{
             id,
+            name,
+            translated_name,
+            race_id: read_mem::<i16>(&proc.handle, hf_addr + df.memory_layout.field_offset(OffsetSection::HistFigure, "hist_race")) as i32,
             fig_info_addr,
-            reputation: read_mem::<usize>(&proc.handle, fig_info_addr + df.memory_layout.field_offset(OffsetSection::HistFigure, "reputation")),
             ..Default::default()
         };
+        hf.links = Relation::read_links(df, proc, *hf_addr);
+        if fig_info_addr == 0 {
+            return hf;
+        }
+
+        hf.reputation = read_mem::<usize>(&proc.handle, fig_info_addr + df.memory_layout.field_offset(OffsetSection::HistFigure, "reputation"));
         hf.read_fake_identity(df, proc);
+        hf.read_kills(df, proc);
+        hf.known_topics = KnownTopic::read_all(df, proc, fig_info_addr);
+        hf.relationships = Relation::read_relationships(df, proc, fig_info_addr);
         hf
     }
 
+    /// The address of a figure's historical_figure_info, 0 if it has none. Kills, knowledge, relationships
+    /// and so on are offsets into this struct, not the figure.
+    pub unsafe fn info_addr(df: &DFInstance, proc: &Process, hf_addr: usize) -> usize {
+        read_mem::<usize>(&proc.handle, hf_addr + df.memory_layout.field_offset(OffsetSection::HistFigure, "hist_fig_info"))
+    }
+
+    pub unsafe fn read_kills(&mut self, df: &DFInstance, proc: &Process) {
+        let kills_addr = read_mem::<usize>(&proc.handle, self.fig_info_addr + df.memory_layout.field_offset(OffsetSection::HistFigure, "kills"));
+        if kills_addr == 0 {
+            return;
+        }
+
+        // named kills are stored as the ids of the events where the victims died
+        for event_id in mem_vec::<i32>(&proc.handle, kills_addr) {
+            let event = match df.find_event(event_id) {
+                Some(e) => e,
+                None => continue,
+            };
+            let victim_id = match event.detail {
+                HistEventDetail::Died { victim_id, .. } => victim_id,
+                _ => continue,
+            };
+            let race_id = match df.historical_figures.get(&victim_id) {
+                Some(&addr) => read_mem::<i16>(&proc.handle, addr + df.memory_layout.field_offset(OffsetSection::HistFigure, "hist_race")) as i32,
+                None => -1,
+            };
+            let (name, translated_name) = df.get_histfig_names(proc, victim_id);
+            self.kills.push(Kill {
+                histfig_id: victim_id,
+                name,
+                translated_name,
+                race_id,
+                race: df.get_race(race_id).map(|r| r.name.clone()).unwrap_or_default(),
+                event_id,
+                year: event.year,
+            });
+        }
+
+        // other kills are parallel vectors of race, undead flags and count
+        let races = mem_vec::<i32>(&proc.handle, kills_addr + df.memory_layout.field_offset(OffsetSection::HistFigure, "killed_race_vector"));
+        let undead = mem_vec::<u16>(&proc.handle, kills_addr + df.memory_layout.field_offset(OffsetSection::HistFigure, "killed_undead_vector"));
+        let counts = mem_vec::<i32>(&proc.handle, kills_addr + df.memory_layout.field_offset(OffsetSection::HistFigure, "killed_counts_vector"));
+        for (i, &race_id) in races.iter().enumerate() {
+            let count = counts.get(i).copied().unwrap_or_default();
+            let is_undead = undead.get(i).copied().unwrap_or_default() != 0;
+            self.total_kills_other += count;
+
+            match self.kill_counts.iter_mut().find(|k| k.race_id == race_id && k.undead == is_undead) {
+                Some(k) => k.count += count,
+                None => {
+                    let race = df.get_race(race_id).map(|r| match count {
+                        1 => r.name.clone(),
+                        _ => r.plural_name.clone(),
+                    });
+                    self.kill_counts.push(KillCount { race_id, race: race.unwrap_or_default(), count, undead: is_undead });
+                }
+            }
+        }
+        self.kill_counts.sort_by_key(|k| Reverse(k.count));
+    }
+
+    pub fn total_kills(&self) -> i32 {
+        self.kills.len() as i32 + self.total_kills_other
+    }
+
     pub unsafe fn read_fake_identity(&mut self, df: &DFInstance, proc: &Process) {
         self.has_fake_identity = false;
-        let id = read_mem::<i32>(&proc.handle, self.fig_info_addr + df.memory_layout.field_offset(OffsetSection::HistFigure, "current_ident"));
+        // the current identity is part of the reputation info
+        if self.reputation == 0 {
+            return;
+        }
+        let id = read_mem::<i32>(&proc.handle, self.reputation + df.memory_layout.field_offset(OffsetSection::HistFigure, "current_ident"));
         let addr = match df.get_fake_identity(id.try_into().unwrap()) {
             Some(a) => a,
             None => return,
@@ -55,19 +177,28 @@ impl HistoricalFigure {
         };
 
         self.fake_identity.fake_name_addr = self.fake_identity.addr + df.memory_layout.field_offset(OffsetSection::HistFigure, "fake_name");
-        self.fake_identity.fake_name = read_mem_as_string(&proc, self.fake_identity.fake_name_addr + df.memory_layout.field_offset(OffsetSection::Word, "first_name"));
-        self.fake_identity.fake_nickname = read_mem_as_string(&proc, self.fake_identity.fake_name_addr + df.memory_layout.field_offset(OffsetSection::Word, "nickname"));
+        let fake_name = Name::new(df, proc, self.fake_identity.fake_name_addr);
+        self.fake_identity.fake_name = capitalize_each(&fake_name.first_name);
+        self.fake_identity.fake_nickname = fake_name.nickname.clone();
+        self.fake_identity.fake_last_name = df.languages.last_name(&fake_name, false);
+        self.fake_identity.fake_translated_last_name = df.languages.last_name(&fake_name, true);
 
-        self.fake_identity.fake_birth_year = read_mem::<i32>(&proc.handle, self.fake_identity.fake_name_addr +
-            df.memory_layout.field_offset(OffsetSection::Word, "birth_year"));
-        self.fake_identity.fake_birth_time = read_mem::<i32>(&proc.handle, self.fake_identity.fake_name_addr +
-            df.memory_layout.field_offset(OffsetSection::Word, "birth_time"));
+        self.fake_identity.fake_birth_year = read_mem::<i32>(&proc.handle, self.fake_identity.addr +
+            df.memory_layout.field_offset(OffsetSection::HistFigure, "fake_birth_year"));
+        self.fake_identity.fake_birth_time = read_mem::<i32>(&proc.handle, self.fake_identity.addr +
+            df.memory_layout.field_offset(OffsetSection::HistFigure, "fake_birth_time"));
     }
+}
 
-    //
-    // TODO: Translated names
-    //
-
+/// A dwarf's combat record, for the veterans view
+#[derive(Debug, Default, Serialize, Deserialize, Clone)]
+pub struct Veteran {
+    pub dwarf_id: i32,
+    pub name: String,
+    pub squad_name: String,
+    pub total_kills: i32,
+    pub notable_kills: Vec<Kill>,
+    pub kill_counts: Vec<KillCount>,
 }
 
 #[derive(Debug, Default, Clone, Serialize, Deserialize)]
@@ -0,0 +1,87 @@
+use serde::{Deserialize, Serialize};
+
+use crate::DFInstance;
+use crate::caste::caste::Caste;
+use crate::data::memorylayout::OffsetSection;
+use crate::items::Item;
+use crate::win::memory::memory::{mem_vec, read_mem};
+use crate::win::process::Process;
+
+/// How a unit is carrying an item
+#[derive(Default, Serialize, Deserialize, Debug, Eq, PartialEq, Hash, Clone, Copy)]
+pub enum InventoryMode {
+    #[default]
+    Hauled = 0,
+    Weapon,
+    Worn,
+    Piercing,
+    Flask,
+    WrappedAround,
+    StuckIn,
+    InMouth,
+    Pet,
+    SewnInto,
+    Strapped,
+}
+
+impl From<i16> for InventoryMode {
+    fn from(value: i16) -> Self {
+        match value {
+            1 => InventoryMode::Weapon,
+            2 => InventoryMode::Worn,
+            3 => InventoryMode::Piercing,
+            4 => InventoryMode::Flask,
+            5 => InventoryMode::WrappedAround,
+            6 => InventoryMode::StuckIn,
+            7 => InventoryMode::InMouth,
+            8 => InventoryMode::Pet,
+            9 => InventoryMode::SewnInto,
+            10 => InventoryMode::Strapped,
+            _ => InventoryMode::Hauled,
+        }
+    }
+}
+
+#[derive(Default, Serialize, Deserialize, Debug, Clone)]
+pub struct InventoryItem {
+    pub item: Item,
+    pub name: String,
+    pub mode: InventoryMode,
+    pub body_part_id: i16,
+    pub body_part: String,
+    /// items inside this one, e.g. the bolts in a quiver
+    pub contained: Vec<Item>,
+}
+
+impl InventoryItem {
+    pub unsafe fn new(df: &DFInstance, proc: &Process, caste: &Caste, addr: usize) -> InventoryItem {
+        let item_addr = read_mem::<usize>(&proc.handle, addr);
+        let item = Item::new(df, proc, item_addr);
+        let contained = item.contained_ids().iter()
+            .filter_map(|&id| df.get_item_addr(id))
+            .map(|a| Item::new(df, proc, a))
+            .collect();
+        let body_part_id = read_mem::<i16>(&proc.handle, addr + df.memory_layout.field_offset(OffsetSection::Dwarf, "inventory_item_bodypart"));
+
+        InventoryItem {
+            name: item.display_name(),
+            item,
+            mode: InventoryMode::from(read_mem::<i16>(&proc.handle, addr + df.memory_layout.field_offset(OffsetSection::Dwarf, "inventory_item_mode"))),
+            body_part_id,
+            body_part: caste.body_part_name(df, proc, body_part_id),
+            contained,
+        }
+    }
+
+    /// Reads every item a unit is holding, wearing or carrying
+    pub unsafe fn read_all(df: &DFInstance, proc: &Process, unit_addr: usize, caste: &Caste) -> Vec<InventoryItem> {
+        mem_vec::<usize>(&proc.handle, unit_addr + df.memory_layout.field_offset(OffsetSection::Dwarf, "inventory"))
+            .iter()
+            .map(|&i| InventoryItem::new(df, proc, caste, i))
+            .collect()
+    }
+
+    pub fn is_equipped(&self) -> bool {
+        matches!(self.mode, InventoryMode::Weapon | InventoryMode::Worn | InventoryMode::Strapped)
+    }
+}
@@ -8,8 +8,11 @@ pub mod material {
     use crate::data::memorylayout::OffsetSection;
     use crate::flagarray::FlagArray;
     use crate::util::memory::read_mem_as_string;
+    use crate::win::memory::memory::mem_vec;
     use crate::win::process::Process;
 
+    /// The inorganic_flags bit for materials generated by worldgen, e.g. divine metals. Bit 0 is LAVA.
+    const INORGANIC_GENERATED: usize = 1;
 
     #[derive(Default, Serialize, Deserialize, Debug, Eq, Hash, PartialEq, Copy, Clone)]
     #[repr(i16)]
@@ -24,6 +27,21 @@ pub mod material {
         Pressed
     }
 
+    impl From<i16> for MaterialState {
+        fn from(value: i16) -> Self {
+            match value {
+                0 => MaterialState::Solid,
+                1 => MaterialState::Liquid,
+                2 => MaterialState::Gas,
+                3 => MaterialState::Powder,
+                4 => MaterialState::Paste,
+                5 => MaterialState::Pressed,
+                _ => MaterialState::Any,
+            }
+        }
+    }
+
+    #[derive(Serialize, Deserialize, Debug, PartialEq, Eq, Hash, Copy, Clone)]
     pub enum MaterialFlag {
         None = -1,
         Bone = 0,
@@ -98,10 +116,92 @@ pub mod material {
         NumOfMaterialFlags = 69
     }
 
+    impl MaterialFlag {
+        /// Every flag that can be read from a material's flag array
+        pub const COUNT: usize = MaterialFlag::NumOfMaterialFlags as usize;
+
+        pub fn from_usize(value: usize) -> Self {
+            match value {
+                0 => MaterialFlag::Bone,
+                1 => MaterialFlag::Meat,
+                2 => MaterialFlag::EdibleVermin,
+                3 => MaterialFlag::EdibleRaw,
+                4 => MaterialFlag::EdibleCooked,
+                5 => MaterialFlag::Alcohol,
+                6 => MaterialFlag::ItemsMetal,
+                7 => MaterialFlag::ItemsBarred,
+                8 => MaterialFlag::ItemsScaled,
+                9 => MaterialFlag::ItemsLeather,
+                10 => MaterialFlag::ItemsSoft,
+                11 => MaterialFlag::ItemsHard,
+                12 => MaterialFlag::ImpliesAnimalKill,
+                13 => MaterialFlag::AlcoholPlant,
+                14 => MaterialFlag::AlcoholCreature,
+                15 => MaterialFlag::CheesePlant,
+                16 => MaterialFlag::CheeseCreature,
+                17 => MaterialFlag::PowderMiscPlant,
+                18 => MaterialFlag::PowderMiscCreature,
+                19 => MaterialFlag::StockpileGlob,
+                20 => MaterialFlag::LiquidMiscPlant,
+                21 => MaterialFlag::LiquidMiscCreature,
+                22 => MaterialFlag::LiquidMiscOther,
+                23 => MaterialFlag::IsWood,
+                24 => MaterialFlag::ThreadPlant,
+                25 => MaterialFlag::Tooth,
+                26 => MaterialFlag::Horn,
+                27 => MaterialFlag::Pearl,
+                28 => MaterialFlag::Shell,
+                29 => MaterialFlag::Leather,
+                30 => MaterialFlag::Silk,
+                31 => MaterialFlag::Soap,
+                32 => MaterialFlag::Rots,
+                33 => MaterialFlag::IsDye,
+                34 => MaterialFlag::IsPowderMisc,
+                35 => MaterialFlag::IsLiquidMisc,
+                36 => MaterialFlag::StructuralPlantMat,
+                37 => MaterialFlag::SeedMat,
+                38 => MaterialFlag::LeafMat,
+                39 => MaterialFlag::IsCheese,
+                40 => MaterialFlag::EntersBlood,
+                41 => MaterialFlag::BloodMapDescriptor,
+                42 => MaterialFlag::IchorMapDescriptor,
+                43 => MaterialFlag::GooMapDescriptor,
+                44 => MaterialFlag::SlimeMapDescriptor,
+                45 => MaterialFlag::PusMapDescriptor,
+                46 => MaterialFlag::GeneratesMiasma,
+                47 => MaterialFlag::IsMetal,
+                48 => MaterialFlag::IsGem,
+                49 => MaterialFlag::IsGlass,
+                50 => MaterialFlag::CrystalGlassable,
+                51 => MaterialFlag::ItemsWeapon,
+                52 => MaterialFlag::ItemsWeaponRanged,
+                53 => MaterialFlag::ItemsAnvil,
+                54 => MaterialFlag::ItemsAmmo,
+                55 => MaterialFlag::ItemsDigger,
+                56 => MaterialFlag::ItemsArmor,
+                57 => MaterialFlag::ItemsDelicate,
+                58 => MaterialFlag::ItemsSiegeEngine,
+                59 => MaterialFlag::ItemsQuern,
+                60 => MaterialFlag::IsStone,
+                61 => MaterialFlag::Undiggable,
+                62 => MaterialFlag::Yarn,
+                63 => MaterialFlag::StockpileGlobPaste,
+                64 => MaterialFlag::StockpileGlobPressed,
+                65 => MaterialFlag::DisplayUnglazed,
+                66 => MaterialFlag::DoNotCleanGlob,
+                67 => MaterialFlag::NoStoneStockpile,
+                68 => MaterialFlag::StockpileThreadMetal,
+                _ => MaterialFlag::None,
+            }
+        }
+    }
+
+
     #[derive(Default, Serialize, Deserialize, Debug, Clone)]
     pub struct Material {
         pub index: i32,
         pub flags: FlagArray,
+        pub material_flags: Vec<MaterialFlag>,
         pub organic: bool,
         pub prefix: String,
         pub state_names: HashMap<MaterialState, String>,
@@ -110,27 +210,24 @@ pub mod material {
 
     impl Material {
         pub unsafe fn new(df: &DFInstance, proc: &Process, index: usize, addr: usize, organic: bool) -> Material {
-
-
             let mut mat = Material {
                 index: index as i32,
-                flags: FlagArray::default(),
                 organic,
-                prefix: String::new(),
-                state_names: HashMap::new(),
-                is_generated: false,
+                ..Default::default()
             };
 
-            mat.prefix = read_mem_as_string(&proc, addr + df.memory_layout.field_offset(OffsetSection::Material, "prefix"));
+            // inorganics wrap the material, so the flags and the material itself are offset from the raw
+            let mut mat_addr = addr;
             if !organic {
-                mat.flags = FlagArray::new(proc, addr + df.memory_layout.field_offset(OffsetSection::Material, "inorganic_flags"));
-                // is_generated?
-                mat.is_generated = true;
-            } else {
-                mat.flags = FlagArray::new(proc, addr + df.memory_layout.field_offset(OffsetSection::Material, "flags"));
+                let inorganic_flags = FlagArray::new(proc, addr + df.memory_layout.field_offset(OffsetSection::Material, "inorganic_flags"));
+                mat.is_generated = inorganic_flags.flags.get(INORGANIC_GENERATED).unwrap_or_default();
+                mat_addr = addr + df.memory_layout.field_offset(OffsetSection::Material, "inorganic_materials_vector");
             }
 
-            mat.load_state_names(df, proc, addr);
+            mat.flags = FlagArray::new(proc, mat_addr + df.memory_layout.field_offset(OffsetSection::Material, "flags"));
+            mat.prefix = read_mem_as_string(proc, mat_addr + df.memory_layout.field_offset(OffsetSection::Material, "prefix"));
+            mat.load_state_names(df, proc, mat_addr);
+            mat.read_material_flags();
 
             // Bad wuju
             //
@@ -156,85 +253,101 @@ pub mod material {
             for (state, name) in state_names.iter() {
                 self.state_names.insert(*state, read_mem_as_string(&proc, addr + df.memory_layout.field_offset(OffsetSection::Material, name)));
             }
+        }
+
+        fn read_material_flags(&mut self) {
+            self.material_flags = (0..MaterialFlag::COUNT)
+                .filter(|&i| self.flags.flags.get(i).unwrap_or_default())
+                .map(MaterialFlag::from_usize)
+                .collect();
+        }
+
+        pub fn has_flag(&self, flag: MaterialFlag) -> bool {
+            self.material_flags.contains(&flag)
+        }
+
+        /// The name of the material in the given state, with its prefix if it has one
+        pub fn name(&self, state: MaterialState) -> String {
+            let state = match state {
+                MaterialState::Any => MaterialState::Solid,
+                s => s,
+            };
+            let name = self.state_names.get(&state).cloned().unwrap_or_default();
+            match self.prefix.is_empty() {
+                true => name,
+                false => format!("{} {}", self.prefix, name),
+            }
+        }
     }
-}
 
+    #[derive(Default, Serialize, Deserialize, Debug, Clone)]
     pub struct Plant {
-        name: String,
-        name_plural: String,
-        leaf_name: String,
-        leaf_plural: String,
-        seed_name: String,
-        seed_plural: String,
-        flags: FlagArray,
-        materials: Vec<Material>,
+        pub index: i32,
+        pub name: String,
+        pub name_plural: String,
+        pub leaf_plural: String,
+        pub seed_plural: String,
+        pub flags: FlagArray,
+        pub materials: Vec<Material>,
     }
 
     impl Plant {
-        pub unsafe fn new(df: &DFInstance, proc: &Process, addr: usize) -> Plant {
-
-            let plant_name = read_mem_as_string(&proc, df.memory_layout.field_offset(OffsetSection::Plant, "name"));
-            let plant_name_plural = read_mem_as_string(&proc, df.memory_layout.field_offset(OffsetSection::Plant, "name_plural"));
-            let leaf_plural = read_mem_as_string(&proc, df.memory_layout.field_offset(OffsetSection::Plant, "name_leaf_plural"));
-            let seed_plural = read_mem_as_string(&proc, df.memory_layout.field_offset(OffsetSection::Plant, "name_seed_plural"));
-            let flags = FlagArray::new(proc, addr + df.memory_layout.field_offset(OffsetSection::Plant, "flags"));
-
-            let p = Plant{
-                name: plant_name,
-                name_plural: plant_name_plural,
-                leaf_name: String::new(),
-                leaf_plural: leaf_plural,
-                seed_name: String::new(),
-                seed_plural: seed_plural,
-                flags: Plant::get_flags(df, proc, addr),
-                materials: Vec::new(),
-            };
-            p
+        pub unsafe fn new(df: &DFInstance, proc: &Process, index: usize, addr: usize) -> Plant {
+            Plant{
+                index:       index as i32,
+                name:        read_mem_as_string(proc, addr + df.memory_layout.field_offset(OffsetSection::Plant, "name")),
+                name_plural: read_mem_as_string(proc, addr + df.memory_layout.field_offset(OffsetSection::Plant, "name_plural")),
+                leaf_plural: read_mem_as_string(proc, addr + df.memory_layout.field_offset(OffsetSection::Plant, "name_leaf_plural")),
+                seed_plural: read_mem_as_string(proc, addr + df.memory_layout.field_offset(OffsetSection::Plant, "name_seed_plural")),
+                flags:       Plant::get_flags(df, proc, addr),
+                materials:   mem_vec::<usize>(&proc.handle, addr + df.memory_layout.field_offset(OffsetSection::Plant, "materials_vector"))
+                    .iter()
+                    .enumerate()
+                    .map(|(i, &m)| Material::new(df, proc, i, m, true))
+                    .collect(),
+            }
         }
 
         pub unsafe fn get_flags(df: &DFInstance, proc: &Process, addr: usize) -> FlagArray {
             let mut flags = FlagArray::new(proc, addr + df.memory_layout.field_offset(OffsetSection::Plant, "flags"));
+            // make room for the custom flags
+            flags.flags.grow(PlantFlags::HasExtracts as usize + 1);
 
-            // TODO: use enum for flags
-            if flags.flags.get(0).unwrap()||
-            flags.flags.get(1).unwrap() ||
-            flags.flags.get(2).unwrap() ||
-            flags.flags.get(3).unwrap() {
-                flags.flags.set(200, true).unwrap();
-            }
+            let has = |f: PlantFlags| flags.flags.get(f as usize).unwrap_or_default();
+            let crop = has(PlantFlags::Spring) || has(PlantFlags::Summer) || has(PlantFlags::Autumn) || has(PlantFlags::Winter);
+            let extracts = has(PlantFlags::ExtractBarrel) || has(PlantFlags::ExtractVial) || has(PlantFlags::ExtractStillVial) || has(PlantFlags::Thread);
 
-            if flags.flags.get(8).unwrap() ||
-            flags.flags.get(9).unwrap() ||
-            flags.flags.get(10).unwrap() ||
-            flags.flags.get(12).unwrap() {
-                flags.flags.set(201, true).unwrap();
-            }
+            let _ = flags.flags.set(PlantFlags::Crop as usize, crop);
+            let _ = flags.flags.set(PlantFlags::HasExtracts as usize, extracts);
             flags
         }
     }
 
+    #[derive(Debug, PartialEq, Copy, Clone)]
     pub enum PlantFlags {
         Spring = 0,
         Summer = 1,
         Autumn = 2,
         Winter = 3,
-        Seed = 5,
-        Drink = 7,
         ExtractBarrel = 8,
         ExtractVial = 9,
         ExtractStillVial = 10,
         Thread = 12,
-        Mill = 13,
-        Sapling = 77,
-        Tree = 78,
         Crop = 200,
         HasExtracts = 201
     }
 }
+use std::fmt;
 use std::hash::Hash;
 
 use serde::{Deserialize, Serialize};
 
+use crate::DFInstance;
+use crate::data::memorylayout::OffsetSection;
+use crate::util::memory::read_mem_as_string;
+use crate::win::memory::memory::{mem_vec, read_mem};
+use crate::win::process::Process;
+
 #[derive(Default, Serialize, Deserialize, Debug, Eq, PartialEq, Hash, Clone, Copy)]
 pub enum ItemType {
     #[default]
@@ -444,4 +557,254 @@ impl ItemType {
             _ => ItemType::None,
         }
     }
-}
\ No newline at end of file
+}
+impl fmt::Display for ItemType {
+    /// lowercase words from the variant name, e.g. `SmallGem` -> "small gem"
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let mut name = String::new();
+        for (i, c) in format!("{:?}", self).chars().enumerate() {
+            if c.is_uppercase() && i > 0 {
+                name.push(' ');
+            }
+            name.push(c.to_ascii_lowercase());
+        }
+        write!(f, "{}", name)
+    }
+}
+
+#[derive(Default, Serialize, Deserialize, Debug, Eq, PartialEq, Hash, Clone, Copy, PartialOrd, Ord)]
+pub enum ItemQuality {
+    #[default]
+    Ordinary = 0,
+    WellCrafted,
+    FinelyCrafted,
+    Superior,
+    Exceptional,
+    Masterful,
+    Artifact,
+}
+
+impl From<i16> for ItemQuality {
+    fn from(value: i16) -> Self {
+        match value {
+            1 => ItemQuality::WellCrafted,
+            2 => ItemQuality::FinelyCrafted,
+            3 => ItemQuality::Superior,
+            4 => ItemQuality::Exceptional,
+            5 => ItemQuality::Masterful,
+            6 => ItemQuality::Artifact,
+            _ => ItemQuality::Ordinary,
+        }
+    }
+}
+
+impl ItemQuality {
+    /// The marks DF puts around an item's name
+    pub fn symbol(&self) -> &'static str {
+        match self {
+            ItemQuality::Ordinary => "",
+            ItemQuality::WellCrafted => "-",
+            ItemQuality::FinelyCrafted => "+",
+            ItemQuality::Superior => "*",
+            ItemQuality::Exceptional => "\u{2261}",
+            ItemQuality::Masterful => "\u{263C}",
+            ItemQuality::Artifact => "!",
+        }
+    }
+}
+
+#[derive(Default, Serialize, Deserialize, Debug, Clone)]
+pub struct Item {
+    pub addr: usize,
+    pub id: i32,
+    pub item_type: ItemType,
+    pub subtype: i16,
+    pub subtype_name: String,
+    pub mat_type: i16,
+    pub mat_index: i32,
+    pub material: String,
+    pub quality: ItemQuality,
+    /// 0 is new, 1 worn (x), 2 threadbare (X), 3 tattered (XX)
+    pub wear: i16,
+    pub stack_size: i32,
+    pub flags: u32,
+    pub refs: Vec<GeneralRef>,
+}
+
+/// Bits of the item flags
+#[derive(Debug, Clone, Copy)]
+pub enum ItemFlag {
+    InJob = 1,
+    Hostile = 2,
+    InInventory = 3,
+    Removed = 4,
+    InBuilding = 5,
+    Foreign = 14,
+    Trader = 15,
+    GarbageCollect = 17,
+    Forbid = 19,
+}
+
+impl Item {
+    pub unsafe fn new(df: &DFInstance, proc: &Process, addr: usize) -> Item {
+        // the item type is returned by the first vtable method
+        let vtable_addr = read_mem::<usize>(&proc.handle, addr);
+        let vtable = read_mem::<usize>(&proc.handle, vtable_addr);
+
+        let mut item = Item {
+            addr,
+            id:         read_mem::<i32>(&proc.handle, addr + df.memory_layout.field_offset(OffsetSection::Item, "id")),
+            item_type:  ItemType::from_i32(read_mem::<i32>(&proc.handle, vtable + 0x1)),
+            subtype:    -1,
+            mat_type:   read_mem::<i16>(&proc.handle, addr + df.memory_layout.field_offset(OffsetSection::Item, "mat_type")),
+            mat_index:  read_mem::<i32>(&proc.handle, addr + df.memory_layout.field_offset(OffsetSection::Item, "mat_index")),
+            quality:    ItemQuality::from(read_mem::<i16>(&proc.handle, addr + df.memory_layout.field_offset(OffsetSection::Item, "quality"))),
+            wear:       read_mem::<i16>(&proc.handle, addr + df.memory_layout.field_offset(OffsetSection::Item, "wear")),
+            stack_size: read_mem::<i32>(&proc.handle, addr + df.memory_layout.field_offset(OffsetSection::Item, "stack_size")),
+            flags:      read_mem::<u32>(&proc.handle, addr + df.memory_layout.field_offset(OffsetSection::Item, "flags")),
+            refs:       mem_vec::<usize>(&proc.handle, addr + df.memory_layout.field_offset(OffsetSection::Item, "general_refs"))
+                .iter()
+                .map(|&r| GeneralRef::new(df, proc, r))
+                .collect(),
+            ..Default::default()
+        };
+
+        // only item types with definitions have an item_def pointer
+        if df.item_defs.contains_key(&item.item_type) {
+            let def_addr = read_mem::<usize>(&proc.handle, addr + df.memory_layout.field_offset(OffsetSection::Item, "item_def"));
+            if def_addr != 0 {
+                item.subtype = read_mem::<i16>(&proc.handle, def_addr + df.memory_layout.field_offset(OffsetSection::ItemSubtype, "sub_type"));
+                item.subtype_name = read_mem_as_string(proc, def_addr + df.memory_layout.field_offset(OffsetSection::ItemSubtype, "name"));
+            }
+        }
+        if let Some(mat) = df.get_material(proc, item.mat_index, item.mat_type) {
+            item.material = mat.name(Default::default());
+        }
+        item
+    }
+
+    pub fn has_flag(&self, flag: ItemFlag) -> bool {
+        self.flags & (1 << flag as u32) != 0
+    }
+
+    /// Only the tests build items by hand, the game sets the flags of real ones
+    #[cfg(test)]
+    pub fn set_flag(&mut self, flag: ItemFlag) {
+        self.flags |= 1 << flag as u32;
+    }
+
+    pub fn is_forbidden(&self) -> bool {
+        self.has_flag(ItemFlag::Forbid)
+    }
+
+    /// Claimed by a job, carried by a unit or built into something
+    pub fn in_use(&self) -> bool {
+        self.has_flag(ItemFlag::InJob) || self.has_flag(ItemFlag::InInventory) || self.has_flag(ItemFlag::InBuilding)
+    }
+
+    /// Whether the item belongs to the fortress, i.e. isn't a trader's, an invader's or already destroyed
+    pub fn is_fortress_stock(&self) -> bool {
+        ![ItemFlag::Hostile, ItemFlag::Removed, ItemFlag::Foreign, ItemFlag::Trader, ItemFlag::GarbageCollect]
+            .iter()
+            .any(|&f| self.has_flag(f))
+    }
+
+    /// Ids of the items inside this one, e.g. the bolts in a quiver
+    pub fn contained_ids(&self) -> Vec<i32> {
+        self.refs.iter()
+            .filter(|r| r.ref_type == GeneralRefType::ContainsItem)
+            .map(|r| r.id)
+            .collect()
+    }
+
+    /// The id of the first reference of a type, -1 if there is none
+    pub fn ref_id(&self, ref_type: GeneralRefType) -> i32 {
+        self.refs.iter().find(|r| r.ref_type == ref_type).map_or(-1, |r| r.id)
+    }
+
+    /// e.g. "*steel battle axe*" or "x(pig tail sock)x"
+    pub fn display_name(&self) -> String {
+        let base = match self.subtype_name.is_empty() {
+            true => self.item_type.to_string(),
+            false => self.subtype_name.clone(),
+        };
+        let q = self.quality.symbol();
+        let mut name = match self.material.is_empty() {
+            true => format!("{q}{base}{q}"),
+            false => format!("{q}{} {base}{q}", self.material),
+        };
+        let w = match self.wear {
+            1 => "x",
+            2 => "X",
+            3 => "XX",
+            _ => "",
+        };
+        if !w.is_empty() {
+            name = format!("{w}({name}){w}");
+        }
+        name
+    }
+}
+
+/// The general reference types used so far, the rest are read as `Other`
+#[derive(Default, Serialize, Deserialize, Debug, Eq, PartialEq, Hash, Clone, Copy)]
+pub enum GeneralRefType {
+    #[default]
+    Other = -1,
+    Artifact = 0,
+    IsArtifact = 1,
+    ContainsItem = 10,
+    ContainedInItem = 11,
+    UnitHolder = 18,
+    HistoricalFigure = 55,
+}
+
+impl From<i32> for GeneralRefType {
+    fn from(value: i32) -> Self {
+        match value {
+            0 => GeneralRefType::Artifact,
+            1 => GeneralRefType::IsArtifact,
+            10 => GeneralRefType::ContainsItem,
+            11 => GeneralRefType::ContainedInItem,
+            18 => GeneralRefType::UnitHolder,
+            55 => GeneralRefType::HistoricalFigure,
+            _ => GeneralRefType::Other,
+        }
+    }
+}
+
+/// A link from an item to another game object
+#[derive(Default, Serialize, Deserialize, Debug, Clone)]
+pub struct GeneralRef {
+    pub ref_type: GeneralRefType,
+    /// the id of the referenced item, artifact, unit or historical figure
+    pub id: i32,
+}
+
+impl GeneralRef {
+    pub unsafe fn new(df: &DFInstance, proc: &Process, addr: usize) -> GeneralRef {
+        // the type is returned by a vtable method
+        let vtable = read_mem::<usize>(&proc.handle, addr);
+        let type_fn = read_mem::<usize>(&proc.handle, vtable + df.memory_layout.field_offset(OffsetSection::GeneralRef, "ref_type"));
+        GeneralRef {
+            ref_type: GeneralRefType::from(read_mem::<i32>(&proc.handle, type_fn + 0x1)),
+            // artifact, item, unit and historical figure refs all keep the id in the same place
+            id: read_mem::<i32>(&proc.handle, addr + df.memory_layout.field_offset(OffsetSection::GeneralRef, "item_id")),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn set_flags() {
+        let mut item = Item::default();
+        item.set_flag(ItemFlag::Forbid);
+        item.set_flag(ItemFlag::InInventory);
+        assert!(item.has_flag(ItemFlag::Forbid) && item.has_flag(ItemFlag::InInventory));
+        assert!(item.is_forbidden());
+        assert_eq!(item.flags, (1 << ItemFlag::Forbid as u32) | (1 << ItemFlag::InInventory as u32));
+    }
+}
@@ -0,0 +1,131 @@
+use std::collections::HashMap;
+
+use serde::{Deserialize, Serialize};
+
+use crate::DFInstance;
+use crate::data::memorylayout::OffsetSection;
+use crate::util::{capitalize_each, memory::read_mem_as_string};
+use crate::win::{memory::memory::read_mem, process::Process};
+
+pub const JOB_ON_BREAK: i32 = -2;
+pub const JOB_NONE: i32 = -3;
+
+/// The unit state id DF uses for dwarves on break
+const STATE_ON_BREAK: i16 = 17;
+
+#[derive(Default, Debug, Clone, Serialize, Deserialize)]
+pub struct Job {
+    pub id: i32,
+    pub name: String,
+    /// the category from unit_jobs, e.g. "Engrave" for "Detail Wall"
+    pub group: String,
+    /// the reaction code for custom jobs, e.g. "BREW_DRINK_FROM_PLANT"
+    pub sub_job: String,
+    pub reaction_name: String,
+    pub mat_type: i16,
+    pub mat_index: i32,
+    pub material: String,
+}
+
+impl Job {
+    /// Reads the job a unit is currently doing. `None` if the unit has no job.
+    pub unsafe fn new(df: &DFInstance, proc: &Process, addr: usize) -> Option<Job> {
+        let job_addr = read_mem::<usize>(&proc.handle, addr + df.memory_layout.field_offset(OffsetSection::Dwarf, "current_job"));
+        if job_addr == 0 {
+            return None;
+        }
+
+        let mut job = Job {
+            id:        read_mem::<i16>(&proc.handle, job_addr + df.memory_layout.field_offset(OffsetSection::Job, "id")) as i32,
+            mat_type:  read_mem::<i16>(&proc.handle, job_addr + df.memory_layout.field_offset(OffsetSection::Job, "mat_type")),
+            mat_index: read_mem::<i32>(&proc.handle, job_addr + df.memory_layout.field_offset(OffsetSection::Job, "mat_index")),
+            sub_job:   read_mem_as_string(proc, job_addr + df.memory_layout.field_offset(OffsetSection::Job, "sub_job_id")),
+            ..Default::default()
+        };
+
+        if let Some(reaction) = df.reactions.get(&job.sub_job) {
+            job.reaction_name = reaction.name.clone();
+        }
+        if let Some(mat) = df.get_material(proc, job.mat_index, job.mat_type) {
+            job.material = mat.name(Default::default());
+        }
+        job.read_name(df);
+        Some(job)
+    }
+
+    /// A placeholder job for units that aren't doing anything
+    pub fn idle(df: &DFInstance, on_break: bool) -> Job {
+        let mut job = Job {
+            id: if on_break { JOB_ON_BREAK } else { JOB_NONE },
+            mat_type: -1,
+            mat_index: -1,
+            ..Default::default()
+        };
+        job.read_name(df);
+        job
+    }
+
+    pub fn is_on_break(states: &HashMap<i16, i32>) -> bool {
+        states.contains_key(&STATE_ON_BREAK)
+    }
+
+    /// Reads the name from unit_jobs, so the material has to be resolved first
+    fn read_name(&mut self, df: &DFInstance) {
+        // jobs are either listed on their own or grouped as sub jobs, ungrouped ones default to id 0
+        for j in &df.game_data.unit_jobs {
+            if let Some(sub) = j.sub.iter().find(|s| s.id == self.id) {
+                // unnamed sub jobs are the group's plain job, e.g. "Remove Construction"
+                let name = match sub.name.is_empty() {
+                    true => &j.name,
+                    false => &sub.name,
+                };
+                self.name = with_material(name, &self.material);
+                self.group = j.name.clone();
+                return;
+            }
+        }
+        if let Some(j) = df.game_data.unit_jobs.iter().find(|j| j.sub.is_empty() && j.id == self.id) {
+            self.name = with_material(&j.name, &self.material);
+            self.group = j.name.clone();
+        }
+    }
+}
+
+/// Fills the "[mat]" placeholder in a job name, e.g. "Forge/Make [mat] Barrel", or drops it if there's no material
+fn with_material(name: &str, material: &str) -> String {
+    name.replace("[mat]", &capitalize_each(material)).split_whitespace().collect::<Vec<&str>>().join(" ")
+}
+
+#[derive(Default, Debug, Clone, Serialize, Deserialize)]
+pub struct Reaction {
+    pub tag: String,
+    pub name: String,
+    pub skill_id: i16,
+}
+
+impl Reaction {
+    pub unsafe fn new(df: &DFInstance, proc: &Process, addr: usize) -> Reaction {
+        Reaction {
+            tag:      read_mem_as_string(proc, addr),
+            name:     capitalize_each(&read_mem_as_string(proc, addr + df.memory_layout.field_offset(OffsetSection::Job, "reaction"))),
+            skill_id: read_mem::<i16>(&proc.handle, addr + df.memory_layout.field_offset(OffsetSection::Job, "reaction_skill")),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn fills_the_material_placeholder() {
+        assert_eq!(with_material("Forge/Make [mat] Barrel", "iron"), "Forge/Make Iron Barrel");
+        assert_eq!(with_material("Construct [mat] Door/Portal", "rough green glass"), "Construct Rough Green Glass Door/Portal");
+    }
+
+    #[test]
+    fn drops_the_placeholder_without_a_material() {
+        assert_eq!(with_material("Forge/Make [mat] Barrel", ""), "Forge/Make Barrel");
+        assert_eq!(with_material("Bring Item to Depot", ""), "Bring Item to Depot");
+    }
+}
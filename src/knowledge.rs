@@ -0,0 +1,131 @@
+use serde::{Deserialize, Serialize};
+
+use crate::DFInstance;
+use crate::activity::{ActivityDetail, ActivityEvent};
+use crate::data::memorylayout::OffsetSection;
+use crate::dwarf::dwarf::Dwarf;
+use crate::win::memory::memory::read_mem;
+use crate::win::process::Process;
+
+/// One topic from knowledge.toml. Topics are grouped into categories of up to 32 flags each.
+#[derive(Default, Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct KnownTopic {
+    pub category: i32,
+    /// the bit of the topic within its category
+    pub flag: i32,
+    /// e.g. "Mathematics"
+    pub field: String,
+    /// e.g. "geometry"
+    pub area: String,
+    pub subject: String,
+}
+
+impl KnownTopic {
+    pub fn new(df: &DFInstance, category: i32, flag: i32) -> Option<KnownTopic> {
+        let k = df.game_data.knowledge.get(category as usize)?;
+        let t = k.topics.get(flag as usize)?;
+        Some(KnownTopic {
+            category,
+            flag,
+            field: k.name.clone(),
+            area: t.area.clone(),
+            subject: t.subject.clone(),
+        })
+    }
+
+    /// Every topic set in one category's flags
+    pub fn from_flags(df: &DFInstance, category: i32, flags: u32) -> Vec<KnownTopic> {
+        (0..32)
+            .filter(|bit| flags & (1 << bit) != 0)
+            .filter_map(|bit| KnownTopic::new(df, category, bit))
+            .collect()
+    }
+
+    /// Reads the topics a historical figure knows. `known_info` is the ninth pointer of df-structures'
+    /// historical_figure_info, after spheres, skills, pets, personality, masterpieces, whereabouts, kills and
+    /// wounds, the same order that puts kills at 0x30 and reputation at 0x58.
+    pub unsafe fn read_all(df: &DFInstance, proc: &Process, fig_info_addr: usize) -> Vec<KnownTopic> {
+        let known_info = read_mem::<usize>(&proc.handle, fig_info_addr + df.memory_layout.field_offset(OffsetSection::HistFigure, "known_info"));
+        if known_info == 0 {
+            return vec![];
+        }
+        let flags_addr = known_info + df.memory_layout.field_offset(OffsetSection::HistFigure, "knowledge_flags");
+        (0..df.game_data.knowledge.len())
+            .flat_map(|c| {
+                let flags = read_mem::<u32>(&proc.handle, flags_addr + c * size_of::<u32>());
+                KnownTopic::from_flags(df, c as i32, flags)
+            })
+            .collect()
+    }
+
+    pub fn same_topic(&self, other: &KnownTopic) -> bool {
+        self.category == other.category && self.flag == other.flag
+    }
+}
+
+/// A topic a scholar is currently working on
+#[derive(Default, Debug, Clone, Serialize, Deserialize)]
+pub struct Research {
+    pub topic: KnownTopic,
+    /// e.g. "Ponder Topic" or "Teach Topic"
+    pub activity: String,
+}
+
+impl Research {
+    /// The research found in a dwarf's activities. The activity stores a category and the topic's flag mask.
+    pub fn from_activities(df: &DFInstance, activities: &[ActivityEvent]) -> Vec<Research> {
+        activities.iter()
+            .filter_map(|e| match e.detail {
+                ActivityDetail::Research { knowledge_category, knowledge_flag } => {
+                    let topics = KnownTopic::from_flags(df, knowledge_category, knowledge_flag as u32);
+                    Some(topics.into_iter().map(|topic| Research { topic, activity: e.name.clone() }))
+                },
+                _ => None,
+            })
+            .flatten()
+            .collect()
+    }
+}
+
+/// Who in the fortress knows or is studying a topic
+#[derive(Default, Debug, Clone, Serialize, Deserialize)]
+pub struct TopicSummary {
+    pub topic: KnownTopic,
+    /// (dwarf id, name)
+    pub known_by: Vec<(i32, String)>,
+    pub researched_by: Vec<(i32, String)>,
+}
+
+/// Every topic known or being researched in the fortress, grouped by topic
+pub fn fortress_knowledge(dwarves: &[Dwarf]) -> Vec<TopicSummary> {
+    let mut summary: Vec<TopicSummary> = vec![];
+    let mut entry = |topic: &KnownTopic| -> usize {
+        match summary.iter().position(|s| s.topic.same_topic(topic)) {
+            Some(idx) => idx,
+            None => {
+                summary.push(TopicSummary { topic: topic.clone(), ..Default::default() });
+                summary.len() - 1
+            }
+        }
+    };
+
+    let mut known = vec![];
+    let mut researched = vec![];
+    for d in dwarves {
+        for t in &d.histfig.known_topics {
+            known.push((entry(t), d.id, d.nice_name.clone()));
+        }
+        for r in &d.research {
+            researched.push((entry(&r.topic), d.id, d.nice_name.clone()));
+        }
+    }
+    for (idx, id, name) in known {
+        summary[idx].known_by.push((id, name));
+    }
+    for (idx, id, name) in researched {
+        summary[idx].researched_by.push((id, name));
+    }
+
+    summary.sort_by_key(|s| (s.topic.category, s.topic.flag));
+    summary
+}
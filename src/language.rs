@@ -20,100 +20,107 @@ pub struct Translation {
     pub words: Vec<String>
 }
 
-impl Languages {
+/// The raw parts of a DF language name, as stored in units, historical figures, artifacts and squads
+#[derive(Default, Debug, Clone)]
+pub struct Name {
+    pub first_name: String,
+    pub nickname: String,
+    /// front compound, rear compound, first adjective, second adjective, hyphen compound, the x, of x
+    words: [i32; 7],
+    parts_of_speech: [i16; 7],
+    language_id: i32,
+}
 
-    pub unsafe fn language_word(&self, df: &DFInstance, proc: &Process, addr: usize) -> String {
-        // front_compound, rear_compound, first_adjective, second_adjective, hypen_compound
-        // the_x, of_x
-        let language_id = read_mem::<i32>(&proc.handle, addr + df.memory_layout.field_offset(OffsetSection::Word, "language_id"));
-        let mut words: Vec<String> = vec![];
+impl Name {
+    pub unsafe fn new(df: &DFInstance, proc: &Process, addr: usize) -> Name {
+        let words_addr = addr + df.memory_layout.field_offset(OffsetSection::Word, "words");
+        let parts_addr = addr + df.memory_layout.field_offset(OffsetSection::Word, "word_type");
+        let mut n = Name {
+            first_name:  read_mem_as_string(proc, addr + df.memory_layout.field_offset(OffsetSection::Word, "first_name")),
+            nickname:    read_mem_as_string(proc, addr + df.memory_layout.field_offset(OffsetSection::Word, "nickname")),
+            language_id: read_mem::<i32>(&proc.handle, addr + df.memory_layout.field_offset(OffsetSection::Word, "language_id")),
+            ..Default::default()
+        };
         for i in 0..7 {
-            let word = read_mem::<i32>(&proc.handle, addr + df.memory_layout.field_offset(OffsetSection::Word, "words"));
-            // not sure why i*4
-            words.push(self.word_chunk(word + i*4, language_id));
+            n.words[i] = read_mem::<i32>(&proc.handle, words_addr + i * size_of::<i32>());
+            n.parts_of_speech[i] = read_mem::<i16>(&proc.handle, parts_addr + i * size_of::<i16>());
         }
+        n
+    }
+}
 
-        let mut first = words[0].clone() + &words[1].clone();
-        first = capitalize_each(&first);
+impl Languages {
+    /// The English last name and epithet, e.g. "Boltedrazor" or "The Bright Gem"
+    pub unsafe fn english_word(&self, df: &DFInstance, proc: &Process, addr: usize) -> String {
+        let name = Name::new(df, proc, addr);
+        join_parts(&[self.last_name(&name, true), self.epithet(&name, true)])
+    }
 
-        let mut second: String = Default::default();
-        let mut third: String = Default::default();
+    /// The whole name, e.g. "Urist Lolokzefon" or, translated, "Urist Boltedrazor"
+    pub fn full_name(&self, name: &Name, english: bool) -> String {
+        join_parts(&[capitalize_each(&name.first_name), self.last_name(name, english), self.epithet(name, english)])
+    }
 
-        if !words[5].is_empty() {
-            let sv = vec![
-                words[2].clone(),
-                words[3].clone(),
-                words[4].clone(),
-                words[5].clone()
-            ];
-            second = capitalize_each(&sv.join(" "));
-        }
+    /// The compound of the first two words
+    pub fn last_name(&self, name: &Name, english: bool) -> String {
+        capitalize_each(&(self.word(name, 0, english) + &self.word(name, 1, english)))
+    }
 
-        if !words[6].is_empty() {
-            third = capitalize_each(&words[6].clone());
+    /// The "the X of Y" part of artifact, squad and site names. Native names just list the words.
+    pub fn epithet(&self, name: &Name, english: bool) -> String {
+        let w: Vec<String> = (2..7).map(|i| self.word(name, i, english)).collect();
+        if !english {
+            return capitalize_each(&w.join(" "));
         }
 
-        first
+        // first adjective, second adjective, hyphen compound, the x, of x
+        let mut parts = vec![];
+        if !w[3].is_empty() {
+            let the_x = match w[2].is_empty() {
+                true => w[3].clone(),
+                false => format!("{}-{}", w[2], w[3]),
+            };
+            parts.push(capitalize_each(&["The", &w[0], &w[1], &the_x].join(" ")));
+        }
+        if !w[4].is_empty() {
+            parts.push(format!("of {}", capitalize_each(&w[4])));
+        }
+        join_parts(&parts)
     }
 
-
-    pub unsafe fn english_word(&self, df: &DFInstance, proc: &Process, addr: usize) -> String {
-        let mut words: Vec<String> = vec![];
-
-        for i in 0..7 {
-            let word_type = WordType::from_i32(
-                read_mem::<i32>(&proc.handle, addr + df.memory_layout.field_offset(OffsetSection::Word, "word_type") + 2*i)
-            );
-
-            let word = Word::new(addr, &proc, &df.memory_layout);
-            words.push(word.get_word_position(word_type));
+    fn word(&self, name: &Name, idx: usize, english: bool) -> String {
+        let word = name.words[idx];
+        match english {
+            true => self.english_chunk(word, name.parts_of_speech[idx]),
+            false => self.word_chunk(word, name.language_id),
         }
+    }
 
-        let mut first = words[0].clone() + &words[1].clone();
-        first = capitalize_each(&first);
-
-        let mut second: String = Default::default();
-        if !words[5].is_empty() {
-            let mut second_vec: Vec<String> = vec![];
-            second_vec.push("The".to_string());
-            second_vec.push(words[2].clone());
-            second_vec.push(words[3].clone());
-            if words[4].is_empty() {
-                second_vec.push(words[5].clone());
-            } else {
-                second_vec.push(words[4].clone() + "-" + &words[5].clone());
-            }
-            second = second_vec.join(" ");
-            second = capitalize_each(&second);
+    pub fn word_chunk(&self, word: i32, lang_id: i32) -> String {
+        if word < 0 {
+            return String::new();
         }
-
-        let mut third: String = Default::default();
-        if !words[6].is_empty() {
-            third = "of ".to_string() + &capitalize_each(&words[6].clone());
+        match self.translation_map.get(&lang_id) {
+            Some(lang_table) => lang_table.words.get(word as usize).cloned().unwrap_or_default(),
+            None => String::new(),
         }
-
-        first
     }
 
-    pub unsafe fn word_chunk(&self, word: i32, lang_id: i32) -> String {
-        if !word.is_positive() || self.words.is_empty() {
-            return "".to_string();
+    pub fn english_chunk(&self, word: i32, part_of_speech: i16) -> String {
+        if word < 0 {
+            return String::new();
+        }
+        match self.words.get(word as usize) {
+            Some(w) => w.get_word_position(WordType::from(part_of_speech)),
+            None => String::new(),
         }
-
-       let result =  match self.translation_map.get(&lang_id) {
-            Some(lang_table) => {
-                if word.is_negative() || word >= lang_table.words.len() as i32 {
-                    return "".to_string();
-                }
-                lang_table.words[word as usize].clone()
-            },
-            None => return "".to_string()
-        };
-        result
     }
-
 }
 
+/// Joins the non-empty parts of a name with spaces
+fn join_parts(parts: &[String]) -> String {
+    parts.iter().filter(|p| !p.is_empty()).cloned().collect::<Vec<String>>().join(" ")
+}
 
 #[derive(Default, Serialize, Deserialize, Debug, Clone)]
 pub struct Word {
@@ -122,6 +129,7 @@ pub struct Word {
     noun: String,
     plural_noun: String,
     adjective: String,
+    prefix: String,
     verb: String,
     present_simple_verb: String,
     past_simple_verb: String,
@@ -135,6 +143,7 @@ pub struct Word {
             let noun                    = read_mem_as_string(&process, address + memory_layout.field_offset(OffsetSection::Word, "noun_singular"));
             let plural_noun             = read_mem_as_string(&process, address + memory_layout.field_offset(OffsetSection::Word, "noun_plural"));
             let adjective               = read_mem_as_string(&process, address + memory_layout.field_offset(OffsetSection::Word, "adjective"));
+            let prefix                  = read_mem_as_string(&process, address + memory_layout.field_offset(OffsetSection::Word, "prefix"));
             let verb                    = read_mem_as_string(&process, address + memory_layout.field_offset(OffsetSection::Word, "verb"));
             let present_simple_verb     = read_mem_as_string(&process, address + memory_layout.field_offset(OffsetSection::Word, "present_simple_verb"));
             let past_simple_verb        = read_mem_as_string(&process, address + memory_layout.field_offset(OffsetSection::Word, "past_simple_verb"));
@@ -147,6 +156,7 @@ pub struct Word {
                 noun,
                 plural_noun,
                 adjective,
+                prefix,
                 verb,
                 present_simple_verb,
                 past_simple_verb,
@@ -160,6 +170,7 @@ pub struct Word {
                 WordType::Noun => self.noun.clone(),
                 WordType::PluralNoun => self.plural_noun.clone(),
                 WordType::Adjective => self.adjective.clone(),
+                WordType::Prefix => self.prefix.clone(),
                 WordType::Verb => self.verb.clone(),
                 WordType::PresentSimpleVerb => self.present_simple_verb.clone(),
                 WordType::PastSimpleVerb => self.past_simple_verb.clone(),
@@ -172,6 +183,7 @@ pub struct Word {
         Noun,
         PluralNoun,
         Adjective,
+        Prefix,
         Verb,
         PresentSimpleVerb,
         PastSimpleVerb,
@@ -179,18 +191,67 @@ pub struct Word {
         PresentParticipleVerb
     }
 
-    impl WordType{
-        fn from_i32(value: i32) -> Self {
+    impl From<i16> for WordType {
+        fn from(value: i16) -> Self {
             match value {
-                0 => WordType::Noun,
                 1 => WordType::PluralNoun,
                 2 => WordType::Adjective,
-                3 => WordType::Verb,
-                4 => WordType::PresentSimpleVerb,
-                5 => WordType::PastSimpleVerb,
-                6 => WordType::PastParticipleVerb,
-                7 => WordType::PresentParticipleVerb,
-                _ => panic!("Invalid WordType")
+                3 => WordType::Prefix,
+                4 => WordType::Verb,
+                5 => WordType::PresentSimpleVerb,
+                6 => WordType::PastSimpleVerb,
+                7 => WordType::PastParticipleVerb,
+                8 => WordType::PresentParticipleVerb,
+                _ => WordType::Noun,
             }
         }
-    }
\ No newline at end of file
+    }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn languages() -> Languages {
+        let word = |noun: &str, adjective: &str| Word { noun: noun.to_string(), adjective: adjective.to_string(), ..Default::default() };
+        Languages {
+            words: vec![word("gem", "bright"), word("sword", "red"), word("flame", "burning")],
+            translation_map: HashMap::from([(0, Translation {
+                name: "DWARF".to_string(),
+                words: vec!["ilas".to_string(), "ngebzo".to_string(), "zefon".to_string()],
+            })]),
+        }
+    }
+
+    /// A name from the word ids in the epithet slots: first adjective, second adjective, hyphen compound, the x, of x
+    fn name(epithet: [i32; 5], parts_of_speech: [i16; 5]) -> Name {
+        let mut n = Name { words: [-1; 7], ..Default::default() };
+        n.words[2..].copy_from_slice(&epithet);
+        n.parts_of_speech[2..].copy_from_slice(&parts_of_speech);
+        n
+    }
+
+    #[test]
+    fn english_epithet_with_adjective() {
+        let n = name([0, -1, -1, 0, -1], [2, 0, 0, 0, 0]);
+        assert_eq!(languages().epithet(&n, true), "The Bright Gem");
+    }
+
+    #[test]
+    fn english_epithet_with_hyphen_and_of() {
+        let n = name([-1, -1, 2, 1, 0], [0, 0, 0, 0, 0]);
+        assert_eq!(languages().epithet(&n, true), "The Flame-sword of Gem");
+    }
+
+    #[test]
+    fn english_epithet_with_only_of() {
+        let n = name([-1, -1, -1, -1, 2], [0, 0, 0, 0, 0]);
+        assert_eq!(languages().epithet(&n, true), "of Flame");
+    }
+
+    #[test]
+    fn native_epithet_lists_the_words() {
+        let n = name([0, -1, -1, 1, 2], [0, 0, 0, 0, 0]);
+        assert_eq!(languages().epithet(&n, false), "Ilas Ngebzo Zefon");
+        assert_eq!(languages().epithet(&Name { words: [-1; 7], ..Default::default() }, false), "");
+    }
+}
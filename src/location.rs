@@ -0,0 +1,200 @@
+use serde::{Deserialize, Serialize};
+
+use crate::DFInstance;
+use crate::data::memorylayout::OffsetSection;
+use crate::dwarf::dwarf::Dwarf;
+use crate::win::memory::memory::read_mem;
+use crate::win::process::Process;
+
+/// The abstract building types a site can have
+#[derive(Default, Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum LocationType {
+    MeadHall,
+    Keep,
+    Temple,
+    DarkTower,
+    Market,
+    Tomb,
+    Dungeon,
+    UnderworldSpire,
+    Tavern,
+    Library,
+    CountingHouse,
+    Guildhall,
+    Tower,
+    Hospital,
+    #[default]
+    Unknown,
+}
+
+impl From<i32> for LocationType {
+    fn from(value: i32) -> Self {
+        match value {
+            0 => LocationType::MeadHall,
+            1 => LocationType::Keep,
+            2 => LocationType::Temple,
+            3 => LocationType::DarkTower,
+            4 => LocationType::Market,
+            5 => LocationType::Tomb,
+            6 => LocationType::Dungeon,
+            7 => LocationType::UnderworldSpire,
+            8 => LocationType::Tavern,
+            9 => LocationType::Library,
+            10 => LocationType::CountingHouse,
+            11 => LocationType::Guildhall,
+            12 => LocationType::Tower,
+            13 => LocationType::Hospital,
+            _ => LocationType::Unknown,
+        }
+    }
+}
+
+/// DF's occupation types. There's no priest, temples are staffed through positions of the
+/// religion's entity, so priests show up in a dwarf's positions instead.
+#[derive(Default, Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum OccupationType {
+    TavernKeeper,
+    Performer,
+    Scholar,
+    Mercenary,
+    MonsterSlayer,
+    Scribe,
+    Messenger,
+    Doctor,
+    Diagnostician,
+    Surgeon,
+    BoneDoctor,
+    #[default]
+    Unknown,
+}
+
+impl From<i32> for OccupationType {
+    fn from(value: i32) -> Self {
+        match value {
+            0 => OccupationType::TavernKeeper,
+            1 => OccupationType::Performer,
+            2 => OccupationType::Scholar,
+            3 => OccupationType::Mercenary,
+            4 => OccupationType::MonsterSlayer,
+            5 => OccupationType::Scribe,
+            6 => OccupationType::Messenger,
+            7 => OccupationType::Doctor,
+            8 => OccupationType::Diagnostician,
+            9 => OccupationType::Surgeon,
+            10 => OccupationType::BoneDoctor,
+            _ => OccupationType::Unknown,
+        }
+    }
+}
+
+/// A job at a location, e.g. the tavern keeper of a tavern. Unfilled occupations have no unit.
+#[derive(Default, Debug, Clone, Serialize, Deserialize)]
+pub struct Occupation {
+    pub id: i32,
+    pub occupation_type: OccupationType,
+    pub histfig_id: i32,
+    pub unit_id: i32,
+    pub location_id: i32,
+    pub site_id: i32,
+}
+
+impl Occupation {
+    pub unsafe fn new(df: &DFInstance, proc: &Process, addr: usize) -> Occupation {
+        let field = |name: &str| read_mem::<i32>(&proc.handle, addr + df.memory_layout.field_offset(OffsetSection::Location, name));
+        Occupation {
+            id: field("occ_id"),
+            occupation_type: OccupationType::from(field("occ_type")),
+            histfig_id: field("occ_histfig"),
+            unit_id: field("occ_unit"),
+            location_id: field("occ_location"),
+            site_id: field("occ_site"),
+        }
+    }
+
+    pub fn is_filled(&self) -> bool {
+        self.unit_id >= 0
+    }
+}
+
+/// A tavern, temple, library or other location in the fortress
+#[derive(Default, Debug, Clone, Serialize, Deserialize)]
+pub struct Location {
+    pub id: i32,
+    pub location_type: LocationType,
+    pub name: String,
+    pub occupations: Vec<Occupation>,
+}
+
+impl Location {
+    pub unsafe fn new(df: &DFInstance, proc: &Process, addr: usize) -> Location {
+        // the location type is returned by the first vtable method
+        let vtable_addr = read_mem::<usize>(&proc.handle, addr);
+        let vtable = read_mem::<usize>(&proc.handle, vtable_addr);
+        Location {
+            id: read_mem::<i32>(&proc.handle, addr + df.memory_layout.field_offset(OffsetSection::Location, "building_id")),
+            location_type: LocationType::from(read_mem::<i32>(&proc.handle, vtable + 0x1)),
+            name: df.read_translated_name(proc, addr + df.memory_layout.field_offset(OffsetSection::Location, "building_name")),
+            occupations: vec![],
+        }
+    }
+}
+
+/// Who works at a location and which of its jobs are empty
+#[derive(Default, Debug, Clone, Serialize, Deserialize)]
+pub struct LocationStaffing {
+    pub location_id: i32,
+    pub location_type: LocationType,
+    pub name: String,
+    /// (unit id, name, occupation)
+    pub staff: Vec<(i32, String, OccupationType)>,
+    pub vacant: Vec<OccupationType>,
+    pub staffed: bool,
+}
+
+impl LocationStaffing {
+    /// Holders are looked up in every unit, not just the dwarves, since visitors can hold occupations too
+    pub fn new(l: &Location, units: &[Dwarf]) -> LocationStaffing {
+        let mut s = LocationStaffing {
+            location_id: l.id,
+            location_type: l.location_type,
+            name: l.name.clone(),
+            ..Default::default()
+        };
+        for o in &l.occupations {
+            if !o.is_filled() {
+                s.vacant.push(o.occupation_type);
+                continue;
+            }
+            let name = units.iter().find(|u| u.id == o.unit_id).map(|u| u.nice_name.clone()).unwrap_or_default();
+            s.staff.push((o.unit_id, name, o.occupation_type));
+        }
+        s.staffed = !s.staff.is_empty();
+        s
+    }
+}
+
+/// A dwarf with more than one occupation
+#[derive(Default, Debug, Clone, Serialize, Deserialize)]
+pub struct MultipleJobs {
+    pub dwarf_id: i32,
+    pub name: String,
+    /// (location name, occupation)
+    pub occupations: Vec<(String, OccupationType)>,
+}
+
+/// Every dwarf holding more than one occupation
+pub fn multiple_jobs(locations: &[Location], dwarves: &[Dwarf]) -> Vec<MultipleJobs> {
+    dwarves.iter()
+        .filter(|d| d.occupations.len() > 1)
+        .map(|d| MultipleJobs {
+            dwarf_id: d.id,
+            name: d.nice_name.clone(),
+            occupations: d.occupations.iter()
+                .map(|o| {
+                    let location = locations.iter().find(|l| l.id == o.location_id).map(|l| l.name.clone()).unwrap_or_default();
+                    (location, o.occupation_type)
+                })
+                .collect(),
+        })
+        .collect()
+}
@@ -1,39 +1,54 @@
 #![allow(unused_imports)]
 #![allow(unused_variables)]
+mod activity;
 mod api;
+mod artifact;
 mod attribute;
+mod counters;
 mod dfinstance;
 mod dwarf;
 mod caste;
 mod thought;
 mod flagarray;
+mod health;
+mod histevent;
+mod inventory;
 mod language;
+mod location;
 mod logger;
+mod mood;
 mod need;
 mod win;
 mod histfigure;
 mod skill;
 mod squad;
+mod stock;
 mod time;
+mod uniform;
+mod unitflags;
 mod syndromes;
 mod items;
+mod job;
+mod knowledge;
 mod preference;
 mod data;
 mod race;
+mod relationship;
+mod religion;
 mod util;
 mod python;
 
 use log::{debug, error, info, warn};
 use logger::{init_logger, logger_display_name};
 use std::{sync::Arc, time::Duration};
-use axum::{routing::get, Router};
+use axum::{routing::{get, post}, Router};
 use python::main::{add_cwd_to_path, read_python_main, create_lib_module};
 use tokio::sync::Mutex;
 
 use pyo3::prelude::*;
 
 use dfinstance::DFInstance;
-use api::{AppState, get_dwarves_handler, get_gamedata_handler};
+use api::{AppState, get_dwarves_handler, get_units_handler, get_alerts_handler, get_gamedata_handler, get_squads_handler, get_squad_equipment_handler, assign_squad_handler, remove_squad_handler, get_activities_handler, get_veterans_handler, get_events_handler, get_artifacts_handler, get_items_handler, get_moods_handler, get_knowledge_handler, get_relations_handler, get_family_tree_handler, get_marriages_handler, get_deities_handler, get_locations_handler, get_multiple_jobs_handler};
 
 const PROCESS_NAME: &str = "Dwarf Fortress.exe";
 
@@ -62,6 +77,25 @@ async fn main() {
                 let rest = Router::new()
                     .route("/data", get(get_gamedata_handler))
                     .route("/dwarves", get(get_dwarves_handler))
+                    .route("/units", get(get_units_handler))
+                    .route("/alerts", get(get_alerts_handler))
+                    .route("/squads", get(get_squads_handler))
+                    .route("/squads/equipment", get(get_squad_equipment_handler))
+                    .route("/squads/assign", post(assign_squad_handler))
+                    .route("/squads/remove", post(remove_squad_handler))
+                    .route("/activities", get(get_activities_handler))
+                    .route("/veterans", get(get_veterans_handler))
+                    .route("/events", get(get_events_handler))
+                    .route("/artifacts", get(get_artifacts_handler))
+                    .route("/items", get(get_items_handler))
+                    .route("/moods", get(get_moods_handler))
+                    .route("/knowledge", get(get_knowledge_handler))
+                    .route("/relations", get(get_relations_handler))
+                    .route("/relations/family", get(get_family_tree_handler))
+                    .route("/relations/marriages", get(get_marriages_handler))
+                    .route("/deities", get(get_deities_handler))
+                    .route("/locations", get(get_locations_handler))
+                    .route("/locations/multiple_jobs", get(get_multiple_jobs_handler))
                     .with_state(state);
 
                 let listener = tokio::net::TcpListener::bind("0.0.0.0:3000").await.unwrap();
@@ -101,11 +135,11 @@ async fn main() {
                 info!("{n} | Process found, loading data...");
                 match df.load_data(&process) {
                     Ok(_) => {
-                        match df.load_dwarves(&process) {
+                        match df.load_units(&process) {
                             Ok(_) => {
-                                info!("{n} | Loaded {} dwarves successfully.", df.dwarves.len());},
+                                info!("{n} | Loaded {} units and {} dwarves successfully.", df.units.len(), df.dwarves.len());},
                             Err(e) => {
-                                error!("{n} | load_dwarves - {e}");
+                                error!("{n} | load_units - {e}");
                                 drop(df);
                                 std::thread::sleep(Duration::from_secs(5));
                                 continue
@@ -122,10 +156,10 @@ async fn main() {
                         if df.is_on_embark_screen(&process) {
 
                             info!("{n} | Embark screen detected, Trying to load data again...");
-                            match df.load_dwarves(&process) {
+                            match df.load_units(&process) {
                                 Ok(_) => {info!("{n} | Dwarves loaded successfully");},
                                 Err(e) => {
-                                    error!("{n} | load_dwarves - {}", e);
+                                    error!("{n} | load_units - {}", e);
                                     drop(df);
                                     std::thread::sleep(Duration::from_secs(5));
                                     continue
@@ -0,0 +1,144 @@
+use serde::{Deserialize, Serialize};
+
+use crate::DFInstance;
+use crate::data::memorylayout::OffsetSection;
+use crate::dwarf::dwarf::{Dwarf, Mood};
+use crate::items::Item;
+use crate::win::memory::memory::{mem_vec, read_mem};
+use crate::win::process::Process;
+
+/// Moods with an id at or below this are strange (artifact) moods
+const LAST_STRANGE_MOOD: i16 = 4;
+
+/// Things any strange mood may ask for on top of its main material
+const DECORATIONS: [&str; 5] = ["gems", "shells", "bones", "cloth", "leather"];
+
+/// A moodable skill with the workshop it's worked at and what the artifact will be made of
+#[derive(Default, Debug, Clone, Serialize, Deserialize)]
+pub struct MoodSkill {
+    pub skill_id: i32,
+    pub skill: String,
+    pub workshop: String,
+    /// the main material the dwarf will demand
+    pub materials: Vec<String>,
+    /// other items that may be demanded as decorations
+    pub extras: Vec<String>,
+}
+
+impl MoodSkill {
+    pub fn new(df: &DFInstance, skill_id: i32, mood: &Mood) -> MoodSkill {
+        let (workshop, material) = match skill_id {
+            0 | 3 | 4 => ("Mason's Workshop", "rock"),
+            1 | 2 => ("Carpenter's Workshop", "wood"),
+            12 | 35 => ("Leather Works", "leather"),
+            13 | 15 => ("Clothier's Shop", "cloth"),
+            26 | 27 | 28 | 33 => ("Metalsmith's Forge", "metal bars"),
+            29 => ("Jeweler's Workshop", "rough gems"),
+            30 => ("Jeweler's Workshop", "cut gems"),
+            31 => ("Craftsdwarf's Workshop", "wood"),
+            32 => ("Craftsdwarf's Workshop", "rock"),
+            34 => ("Glass Furnace", "raw glass"),
+            36 => ("Craftsdwarf's Workshop", "bones"),
+            48 => ("Bowyer's Workshop", "wood"),
+            54 => ("Mechanic's Workshop", "rock"),
+            _ => ("Craftsdwarf's Workshop", "any craft material"),
+        };
+
+        let mut materials = vec![material.to_string()];
+        match mood {
+            Mood::Macabre => materials.extend(["bones", "skulls"].map(String::from)),
+            // fell moods take the body of whoever they kill
+            Mood::Fell => materials.push("a corpse".to_string()),
+            _ => (),
+        }
+
+        MoodSkill {
+            skill_id,
+            skill: df.game_data.skills.get(skill_id as usize).map(|s| s.name.clone()).unwrap_or_default(),
+            workshop: workshop.to_string(),
+            extras: DECORATIONS.iter().filter(|&&e| e != material).map(|e| e.to_string()).collect(),
+            materials,
+        }
+    }
+
+    pub fn is_moodable(df: &DFInstance, skill_id: i32) -> bool {
+        df.game_data.skills.get(skill_id as usize).is_some_and(|s| s.mood == 1)
+    }
+}
+
+/// A strange mood in progress
+#[derive(Default, Debug, Clone, Serialize, Deserialize)]
+pub struct MoodInfo {
+    pub mood: Mood,
+    pub skill: MoodSkill,
+    /// items already brought to the workshop
+    pub claimed_items: Vec<Item>,
+}
+
+impl MoodInfo {
+    /// Reads the strange mood of the unit at `addr`. `None` if it isn't in one.
+    pub unsafe fn new(df: &DFInstance, proc: &Process, addr: usize, mood_id: i16) -> Option<MoodInfo> {
+        if !(0..=LAST_STRANGE_MOOD).contains(&mood_id) {
+            return None;
+        }
+        let mood = Mood::from(mood_id);
+        let skill_id = read_mem::<i16>(&proc.handle, addr + df.memory_layout.field_offset(OffsetSection::Dwarf, "mood_skill")) as i32;
+
+        // the claimed items are in the unit's used items, each entry starts with the item id
+        let claimed_items = mem_vec::<usize>(&proc.handle, addr + df.memory_layout.field_offset(OffsetSection::Dwarf, "used_items_vector"))
+            .iter()
+            .filter_map(|&used| df.get_item_addr(read_mem::<i32>(&proc.handle, used)))
+            .map(|item| Item::new(df, proc, item))
+            .collect();
+
+        Some(MoodInfo {
+            skill: MoodSkill::new(df, skill_id, &mood),
+            mood,
+            claimed_items,
+        })
+    }
+}
+
+/// A dwarf who can still be struck by a strange mood
+#[derive(Default, Debug, Clone, Serialize, Deserialize)]
+pub struct MoodCandidate {
+    pub dwarf_id: i32,
+    pub name: String,
+    /// their highest moodable skills, the mood picks one of these. Empty if they have none.
+    pub likely_skills: Vec<MoodSkill>,
+}
+
+#[derive(Default, Debug, Clone, Serialize, Deserialize)]
+pub struct MoodReport {
+    /// (dwarf id, name, mood) for everyone currently in a strange mood
+    pub current: Vec<(i32, String, MoodInfo)>,
+    pub eligible: Vec<MoodCandidate>,
+}
+
+impl MoodReport {
+    pub fn new(df: &DFInstance, dwarves: &[Dwarf]) -> MoodReport {
+        let mut r = MoodReport::default();
+        for d in dwarves {
+            if let Some(m) = &d.strange_mood {
+                r.current.push((d.id, d.nice_name.clone(), m.clone()));
+                continue;
+            }
+            // only one artifact per lifetime, and the insane or melancholy never recover
+            if !d.is_adult() || d.had_mood || d.locked_mood {
+                continue;
+            }
+
+            let moodable: Vec<_> = d.skills.iter().filter(|s| MoodSkill::is_moodable(df, s.id())).collect();
+            let best = moodable.iter().map(|s| s.raw_level()).max();
+            r.eligible.push(MoodCandidate {
+                dwarf_id: d.id,
+                name: d.nice_name.clone(),
+                likely_skills: moodable.iter()
+                    .filter(|s| Some(s.raw_level()) == best)
+                    .map(|s| MoodSkill::new(df, s.id(), &Mood::Fey))
+                    .collect(),
+            });
+        }
+        r
+    }
+}
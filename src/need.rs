@@ -15,19 +15,73 @@ pub struct Need {
     focus_level: FocusLevel,
     adjective: String,
     description: String,
+    translated_description: String,
     degree_adjective: String,
 }
 
 impl Need {
     pub unsafe fn new (df: &DFInstance, proc: &Process, address: usize) -> Self {
-        Need {
+        let mut n = Need {
             id:          read_mem::<i32>(&proc.handle, address + df.memory_layout.field_offset(OffsetSection::Need, "id")),
             deity_id:    read_mem::<i32>(&proc.handle, address + df.memory_layout.field_offset(OffsetSection::Need, "deity_id")),
             need_level:  read_mem::<i32>(&proc.handle, address + df.memory_layout.field_offset(OffsetSection::Need, "need_level")),
             focus_level: FocusLevel::new(df, proc, address),
             ..Default::default()
-        }
+        };
+
+        let deity_names = match n.deity_id {
+            id if id >= 0 => df.get_histfig_names(proc, id),
+            _ => Default::default(),
+        };
+        n.describe(df, &deity_names);
+        n
+    }
+
+    /// Fills in the name and the sentence the game shows, e.g. "is badly distracted after being unable to pray to Ber",
+    /// with the deity's native and English names
+    fn describe(&mut self, df: &DFInstance, deity_names: &(String, String)) {
+        let info = match df.game_data.needs.iter().find(|n| n.id == self.id) {
+            Some(info) => info,
+            None => return,
+        };
+        self.name = info.name.clone();
+        self.adjective = self.focus_level.to_string().to_lowercase();
+        self.degree_adjective = match self.need_level {
+            l if l >= 10 => "intense",
+            l if l >= 5 => "strong",
+            l if l >= 2 => "moderate",
+            _ => "slight",
+        }.to_string();
+
+        let sentence = |deity_name: &str| {
+            let text = match (self.is_met(), deity_name.is_empty()) {
+                (true, true) => info.positive.clone(),
+                (false, true) => info.negative.clone(),
+                (true, false) => info.positive_deity.replace("%1", deity_name),
+                (false, false) => info.negative_deity.replace("%1", deity_name),
+            };
+            format!("is {} after {}", self.adjective, text)
+        };
+        let (description, translated_description) = (sentence(&deity_names.0), sentence(&deity_names.1));
+        self.description = description;
+        self.translated_description = translated_description;
+    }
+
+    pub fn is_met(&self) -> bool {
+        self.focus_level.degree >= FocusDegree::NotDistracted
+    }
+
+    /// The histfig id of the deity a prayer need is for, -1 otherwise
+    pub fn deity_id(&self) -> i32 {
+        self.deity_id
+    }
 
+    pub fn need_level(&self) -> i32 {
+        self.need_level
+    }
+
+    pub fn focus_level(&self) -> i32 {
+        self.focus_level.level
     }
 }
 
@@ -72,7 +126,7 @@ impl fmt::Display for FocusLevel {
     }
 }
 
-#[derive(Default, Debug, Clone, Serialize, Deserialize)]
+#[derive(Default, Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
 enum FocusDegree {
     BadlyDistracted,
     Distacted,
@@ -82,4 +136,33 @@ enum FocusDegree {
     Untroubled,
     LevelHeaded,
     Unfettered
-}
\ No newline at end of file
+}
+
+/// How focused a dwarf is overall, from how well all of their needs are met
+#[derive(Default, Debug, Clone, Serialize, Deserialize)]
+pub struct Focus {
+    pub current: i32,
+    pub undistracted: i32,
+    /// current focus as a percentage of the undistracted focus
+    pub percent: i32,
+    pub label: String,
+}
+
+impl Focus {
+    pub unsafe fn new(df: &DFInstance, proc: &Process, personality_addr: usize) -> Self {
+        let current = read_mem::<i32>(&proc.handle, personality_addr + df.memory_layout.field_offset(OffsetSection::Soul, "current_focus"));
+        let undistracted = read_mem::<i32>(&proc.handle, personality_addr + df.memory_layout.field_offset(OffsetSection::Soul, "undistracted_focus"));
+        let percent = match undistracted {
+            0 => 100,
+            u => current * 100 / u,
+        };
+        let label = match percent {
+            p if p >= 140 => "Very Focused",
+            p if p >= 120 => "Focused",
+            p if p >= 80 => "Not Distracted",
+            p if p >= 60 => "Distracted",
+            _ => "Badly Distracted",
+        };
+        Focus { current, undistracted, percent, label: label.to_string() }
+    }
+}
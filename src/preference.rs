@@ -1,10 +1,13 @@
 use serde::{Deserialize, Serialize};
 
 use crate::DFInstance;
+use crate::data::memorylayout::OffsetSection;
 use crate::items::material::MaterialState;
 use crate::items::ItemType;
+use crate::util::memory::read_mem_as_string;
 use crate::win::{memory::memory::read_mem, process::Process};
 
+#[derive(Default, Debug, Clone, Serialize, Deserialize)]
 pub struct Preference {
     pub pref_type: PreferenceType,
     pub id: i32,
@@ -13,55 +16,98 @@ pub struct Preference {
     pub mat_index: i32,
     pub mat_state: MaterialState,
     pub item_type: ItemType,
+    /// the readable name of whatever is liked, e.g. "iron" or "giant eagles"
+    pub name: String,
 }
 
 impl Preference {
-    pub unsafe fn new(df: &DFInstance, proc: &Process, addr: usize) {
+    pub unsafe fn new(df: &DFInstance, proc: &Process, addr: usize) -> Preference {
         let id = read_mem::<i32>(&proc.handle, addr + 0x4);
-        let p = Preference{
+        let mut p = Preference{
             id,
-            pref_type:    read_mem::<PreferenceType>(&proc.handle, addr),
-            item_subtype: read_mem::<i32>(&proc.handle, addr + 0x8),
-            mat_type:     read_mem::<i32>(&proc.handle, addr + 0xC),
+            pref_type:    PreferenceType::from(read_mem::<i16>(&proc.handle, addr)),
+            item_subtype: read_mem::<i16>(&proc.handle, addr + 0x8) as i32,
+            mat_type:     read_mem::<i16>(&proc.handle, addr + 0xC) as i32,
             mat_index:    read_mem::<i32>(&proc.handle, addr + 0x10),
-            mat_state:    read_mem::<MaterialState>(&proc.handle, addr + 0x14),
+            mat_state:    MaterialState::from(read_mem::<i16>(&proc.handle, addr + 0x14)),
             item_type:    ItemType::from_i32(id),
+            ..Default::default()
         };
 
-        // TODO: preference stuff
-        match p.pref_type {
-            PreferenceType::LikeMaterial => {
-            }
-            PreferenceType::LikeCreature => {
+        p.name = match p.pref_type {
+            PreferenceType::LikeMaterial => p.material_name(df, proc),
+            PreferenceType::LikeCreature | PreferenceType::HateCreature => {
+                match df.get_race(p.id) {
+                    Some(race) => race.plural_name.clone(),
+                    None => String::new(),
+                }
             }
             PreferenceType::LikeFood => {
+                let mat_name = p.material_name(df, proc);
+                match p.item_type {
+                    // the material name is all there is for these, e.g. "dwarven wine"
+                    ItemType::Drink | ItemType::Meat | ItemType::Fish | ItemType::Cheese |
+                    ItemType::Plant | ItemType::LiquidMisc | ItemType::PowderMisc | ItemType::Egg => mat_name,
+                    _ => format!("{} {}", mat_name, p.item_type).trim().to_string(),
+                }
             }
-            PreferenceType::HateCreature => {
-            }
-            PreferenceType::LikeItem => {
-            }
-            PreferenceType::LikePlant => {
-            }
-            PreferenceType::LikeTree => {
+            PreferenceType::LikeItem => p.item_name(df, proc),
+            PreferenceType::LikePlant | PreferenceType::LikeTree => {
+                match df.plants.get(p.id as usize) {
+                    Some(plant) if p.id >= 0 => plant.name_plural.clone(),
+                    _ => String::new(),
+                }
             }
             PreferenceType::LikeColor => {
+                p.vector_string(proc, &df.color_vector, df.memory_layout.field_offset(OffsetSection::Descriptor, "color_name"))
             }
             PreferenceType::LikeShape => {
+                p.vector_string(proc, &df.shape_vector, df.memory_layout.field_offset(OffsetSection::Descriptor, "shape_name_plural"))
             }
-            PreferenceType::LikePoetry => {
-            }
-            PreferenceType::LikeMusic => {
-            }
-            PreferenceType::LikeDance => {
-            }
-            PreferenceType::LikeOutdoors => {
-            }
-            _ => {}
+            PreferenceType::LikePoetry => p.art_name(df, proc, &df.poetry_vector),
+            PreferenceType::LikeMusic => p.art_name(df, proc, &df.music_vector),
+            PreferenceType::LikeDance => p.art_name(df, proc, &df.dance_vector),
+            PreferenceType::LikeOutdoors => "outdoors".to_string(),
+            PreferenceType::LikesNone => String::new(),
         };
+
+        p
+    }
+
+    unsafe fn material_name(&self, df: &DFInstance, proc: &Process) -> String {
+        match df.get_material(proc, self.mat_index, self.mat_type as i16) {
+            Some(mat) => mat.name(self.mat_state),
+            None => String::new(),
+        }
+    }
+
+    unsafe fn item_name(&self, df: &DFInstance, proc: &Process) -> String {
+        let subtype_addr = df.item_defs.get(&self.item_type)
+            .and_then(|defs| defs.get(self.item_subtype as usize))
+            .filter(|_| self.item_subtype >= 0);
+
+        match subtype_addr {
+            Some(&addr) => read_mem_as_string(proc, addr + df.memory_layout.field_offset(OffsetSection::ItemSubtype, "name_plural")),
+            None => self.item_type.to_string(),
+        }
+    }
+
+    unsafe fn vector_string(&self, proc: &Process, vector: &[usize], offset: usize) -> String {
+        match vector.get(self.id as usize) {
+            Some(&addr) if self.id >= 0 => read_mem_as_string(proc, addr + offset),
+            _ => String::new(),
+        }
+    }
+
+    unsafe fn art_name(&self, df: &DFInstance, proc: &Process, vector: &[usize]) -> String {
+        match vector.get(self.id as usize) {
+            Some(&addr) if self.id >= 0 => df.languages.english_word(df, proc, addr + df.memory_layout.field_offset(OffsetSection::Art, "name")),
+            _ => String::new(),
+        }
     }
 }
 
-#[derive(Debug, Default)]
+#[derive(Debug, Default, PartialEq, Clone, Copy, Serialize, Deserialize)]
 pub enum PreferenceType {
     #[default]
     LikesNone = -1,
@@ -80,6 +126,27 @@ pub enum PreferenceType {
     LikeOutdoors = 99,
 }
 
+impl From<i16> for PreferenceType {
+    fn from(value: i16) -> Self {
+        match value {
+            0 => PreferenceType::LikeMaterial,
+            1 => PreferenceType::LikeCreature,
+            2 => PreferenceType::LikeFood,
+            3 => PreferenceType::HateCreature,
+            4 => PreferenceType::LikeItem,
+            5 => PreferenceType::LikePlant,
+            6 => PreferenceType::LikeTree,
+            7 => PreferenceType::LikeColor,
+            8 => PreferenceType::LikeShape,
+            9 => PreferenceType::LikePoetry,
+            10 => PreferenceType::LikeMusic,
+            11 => PreferenceType::LikeDance,
+            99 => PreferenceType::LikeOutdoors,
+            _ => PreferenceType::LikesNone,
+        }
+    }
+}
+
 #[derive(Debug, PartialEq, Serialize, Deserialize, Clone, Copy)]
 pub enum Commitment {
     Uninterested = 0,
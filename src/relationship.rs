@@ -0,0 +1,250 @@
+use serde::{Deserialize, Serialize};
+
+use crate::DFInstance;
+use crate::data::memorylayout::OffsetSection;
+use crate::dwarf::dwarf::Dwarf;
+use crate::win::memory::memory::{mem_vec, read_mem};
+use crate::win::process::Process;
+
+/// Relationship ranks at or above these count as friends and close friends
+const FRIEND_RANK: i32 = 40;
+const CLOSE_FRIEND_RANK: i32 = 75;
+const FRIENDLY_TERMS_RANK: i32 = 10;
+
+// df-structures' vague_relationship_type values that count as grudges
+const JEALOUS_RELATIONSHIP_GRUDGE: i16 = 3;
+const ATHLETIC_RIVAL: i16 = 9;
+const BUSINESS_RIVAL: i16 = 10;
+const RELIGIOUS_RIVAL: i16 = 11;
+const GRUDGE: i16 = 12;
+const GRUDGE_TYPES: [i16; 5] = [JEALOUS_RELATIONSHIP_GRUDGE, ATHLETIC_RIVAL, BUSINESS_RIVAL, RELIGIOUS_RIVAL, GRUDGE];
+
+/// The histfig_hf_link types followed by the kinds of unit relationship
+#[derive(Default, Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum RelationType {
+    Mother = 0,
+    Father,
+    Spouse,
+    Child,
+    Deity,
+    Lover,
+    Prisoner,
+    Imprisoner,
+    Master,
+    Apprentice,
+    Companion,
+    FormerMaster,
+    FormerApprentice,
+    PetOwner,
+    FormerSpouse,
+    DeceasedSpouse,
+    #[default]
+    Acquaintance = 100,
+    FriendlyTerms,
+    Friend,
+    CloseFriend,
+    Grudge,
+}
+
+impl RelationType {
+    fn from_link(value: i32) -> Option<Self> {
+        match value {
+            0 => Some(RelationType::Mother),
+            1 => Some(RelationType::Father),
+            2 => Some(RelationType::Spouse),
+            3 => Some(RelationType::Child),
+            4 => Some(RelationType::Deity),
+            5 => Some(RelationType::Lover),
+            6 => Some(RelationType::Prisoner),
+            7 => Some(RelationType::Imprisoner),
+            8 => Some(RelationType::Master),
+            9 => Some(RelationType::Apprentice),
+            10 => Some(RelationType::Companion),
+            11 => Some(RelationType::FormerMaster),
+            12 => Some(RelationType::FormerApprentice),
+            13 => Some(RelationType::PetOwner),
+            14 => Some(RelationType::FormerSpouse),
+            15 => Some(RelationType::DeceasedSpouse),
+            _ => None,
+        }
+    }
+
+    fn from_rank(rank: i32, vague_type: i16) -> Self {
+        match rank {
+            _ if GRUDGE_TYPES.contains(&vague_type) => RelationType::Grudge,
+            r if r >= CLOSE_FRIEND_RANK => RelationType::CloseFriend,
+            r if r >= FRIEND_RANK => RelationType::Friend,
+            r if r >= FRIENDLY_TERMS_RANK => RelationType::FriendlyTerms,
+            _ => RelationType::Acquaintance,
+        }
+    }
+
+    pub fn is_family(&self) -> bool {
+        matches!(self, RelationType::Mother | RelationType::Father | RelationType::Spouse | RelationType::Child)
+    }
+}
+
+/// A link from one historical figure to another
+#[derive(Default, Debug, Clone, Serialize, Deserialize)]
+pub struct Relation {
+    pub histfig_id: i32,
+    pub name: String,
+    pub translated_name: String,
+    pub relation: RelationType,
+    /// link strength for family links, the relationship rank for the rest
+    pub strength: i32,
+}
+
+impl Relation {
+    /// Reads a historical figure's family and other hf links. `hf_links` is the figure's histfig_links vector,
+    /// after entity_links and site_links and just before the info pointer.
+    pub unsafe fn read_links(df: &DFInstance, proc: &Process, hf_addr: usize) -> Vec<Relation> {
+        mem_vec::<usize>(&proc.handle, hf_addr + df.memory_layout.field_offset(OffsetSection::HistFigure, "hf_links"))
+            .iter()
+            .filter_map(|&addr| {
+                // the link type is returned by the first vtable method
+                let vtable_addr = read_mem::<usize>(&proc.handle, addr);
+                let vtable = read_mem::<usize>(&proc.handle, vtable_addr);
+                let relation = RelationType::from_link(read_mem::<i32>(&proc.handle, vtable + 0x1))?;
+                let histfig_id = read_mem::<i32>(&proc.handle, addr + df.memory_layout.field_offset(OffsetSection::HistFigure, "hf_link_target"));
+                let (name, translated_name) = df.get_histfig_names(proc, histfig_id);
+                Some(Relation {
+                    histfig_id,
+                    name,
+                    translated_name,
+                    relation,
+                    strength: read_mem::<i32>(&proc.handle, addr + df.memory_layout.field_offset(OffsetSection::HistFigure, "hf_link_strength")),
+                })
+            })
+            .collect()
+    }
+
+    /// Reads the friends, acquaintances and grudges a historical figure has. `relationships` is the pointer after
+    /// reputation in historical_figure_info.
+    pub unsafe fn read_relationships(df: &DFInstance, proc: &Process, fig_info_addr: usize) -> Vec<Relation> {
+        let rel_addr = read_mem::<usize>(&proc.handle, fig_info_addr + df.memory_layout.field_offset(OffsetSection::HistFigure, "relationships"));
+        if rel_addr == 0 {
+            return vec![];
+        }
+        mem_vec::<usize>(&proc.handle, rel_addr)
+            .iter()
+            .map(|&addr| {
+                let histfig_id = read_mem::<i32>(&proc.handle, addr + df.memory_layout.field_offset(OffsetSection::HistFigure, "rel_histfig_id"));
+                let rank = read_mem::<i8>(&proc.handle, addr + df.memory_layout.field_offset(OffsetSection::HistFigure, "rel_rank")) as i32;
+                let vague_type = read_mem::<i16>(&proc.handle, addr + df.memory_layout.field_offset(OffsetSection::HistFigure, "rel_type"));
+                let (name, translated_name) = df.get_histfig_names(proc, histfig_id);
+                Relation {
+                    histfig_id,
+                    name,
+                    translated_name,
+                    relation: RelationType::from_rank(rank, vague_type),
+                    strength: rank,
+                }
+            })
+            .collect()
+    }
+}
+
+#[derive(Default, Debug, Clone, Serialize, Deserialize)]
+pub struct RelationNode {
+    pub histfig_id: i32,
+    /// the unit id if they live in the fortress, -1 otherwise
+    pub dwarf_id: i32,
+    pub name: String,
+}
+
+#[derive(Default, Debug, Clone, Serialize, Deserialize)]
+pub struct RelationEdge {
+    pub from: i32,
+    pub to: i32,
+    pub relation: RelationType,
+    pub strength: i32,
+}
+
+/// Historical figures and the links between them, keyed by histfig id
+#[derive(Default, Debug, Clone, Serialize, Deserialize)]
+pub struct RelationGraph {
+    pub nodes: Vec<RelationNode>,
+    pub edges: Vec<RelationEdge>,
+}
+
+impl RelationGraph {
+    fn add_node(&mut self, histfig_id: i32, name: &str, dwarves: &[Dwarf]) {
+        if self.nodes.iter().any(|n| n.histfig_id == histfig_id) {
+            return;
+        }
+        let dwarf_id = dwarves.iter().find(|d| d.histfig_id == histfig_id).map_or(-1, |d| d.id);
+        self.nodes.push(RelationNode { histfig_id, dwarf_id, name: name.to_string() });
+    }
+
+    fn add_relations<'a>(&mut self, d: &Dwarf, relations: impl Iterator<Item = &'a Relation>, dwarves: &[Dwarf]) {
+        self.add_node(d.histfig_id, &d.nice_name, dwarves);
+        for r in relations {
+            self.add_node(r.histfig_id, &r.name, dwarves);
+            self.edges.push(RelationEdge { from: d.histfig_id, to: r.histfig_id, relation: r.relation, strength: r.strength });
+        }
+    }
+
+    /// Everyone one dwarf is linked to
+    pub fn for_dwarf(d: &Dwarf, dwarves: &[Dwarf]) -> RelationGraph {
+        let mut g = RelationGraph::default();
+        g.add_relations(d, d.histfig.links.iter().chain(d.histfig.relationships.iter()), dwarves);
+        g
+    }
+
+    /// Parents, children and spouses of every dwarf in the fortress
+    pub fn family_tree(dwarves: &[Dwarf]) -> RelationGraph {
+        let mut g = RelationGraph::default();
+        for d in dwarves.iter().filter(|d| d.histfig_id >= 0) {
+            g.add_relations(d, d.histfig.links.iter().filter(|r| r.relation.is_family()), dwarves);
+        }
+        g
+    }
+}
+
+/// A married couple where at least one spouse lives in the fortress
+#[derive(Default, Debug, Clone, Serialize, Deserialize)]
+pub struct Marriage {
+    pub dwarf_id: i32,
+    pub name: String,
+    pub spouse_histfig_id: i32,
+    /// -1 if the spouse doesn't live in the fortress
+    pub spouse_dwarf_id: i32,
+    pub spouse_name: String,
+    /// (dwarf id, name) of their children in the fortress that aren't adults yet
+    pub young_children: Vec<(i32, String)>,
+    /// both spouses are in squads and have young children
+    pub both_in_military: bool,
+}
+
+/// Every marriage in the fortress, listing each couple once
+pub fn marriages(dwarves: &[Dwarf]) -> Vec<Marriage> {
+    let mut list: Vec<Marriage> = vec![];
+    for d in dwarves {
+        for r in d.histfig.links.iter().filter(|r| r.relation == RelationType::Spouse) {
+            let spouse = dwarves.iter().find(|s| s.histfig_id == r.histfig_id);
+            if spouse.is_some_and(|s| list.iter().any(|m| m.dwarf_id == s.id)) {
+                continue;
+            }
+
+            let young_children: Vec<(i32, String)> = d.histfig.links.iter()
+                .filter(|c| c.relation == RelationType::Child)
+                .filter_map(|c| dwarves.iter().find(|k| k.histfig_id == c.histfig_id))
+                .filter(|k| !k.is_adult())
+                .map(|k| (k.id, k.nice_name.clone()))
+                .collect();
+            let in_squad = |x: &Dwarf| x.squad.addr != 0;
+
+            list.push(Marriage {
+                dwarf_id: d.id,
+                name: d.nice_name.clone(),
+                spouse_histfig_id: r.histfig_id,
+                spouse_dwarf_id: spouse.map_or(-1, |s| s.id),
+                spouse_name: r.name.clone(),
+                both_in_military: !young_children.is_empty() && in_squad(d) && spouse.is_some_and(in_squad),
+                young_children,
+            });
+        }
+    }
+    list
+}
@@ -0,0 +1,140 @@
+use std::cmp::Reverse;
+
+use serde::{Deserialize, Serialize};
+
+use crate::DFInstance;
+use crate::activity::{ActivityDetail, ActivityEvent};
+use crate::data::memorylayout::OffsetSection;
+use crate::dwarf::dwarf::Dwarf;
+use crate::histfigure::HistoricalFigure;
+use crate::need::Need;
+use crate::relationship::RelationType;
+use crate::win::memory::memory::{mem_vec, read_mem};
+use crate::win::process::Process;
+
+/// A god worshipped by someone in the fortress
+#[derive(Default, Debug, Clone, Serialize, Deserialize)]
+pub struct Deity {
+    pub histfig_id: i32,
+    pub name: String,
+    pub translated_name: String,
+    /// e.g. "Caverns" or "Fortresses"
+    pub spheres: Vec<String>,
+}
+
+impl Deity {
+    /// `None` if the historical figure isn't loaded
+    pub unsafe fn new(df: &DFInstance, proc: &Process, histfig_id: i32) -> Option<Deity> {
+        let &hf_addr = df.historical_figures.get(&histfig_id)?;
+        let fig_info_addr = HistoricalFigure::info_addr(df, proc, hf_addr);
+
+        // the spheres struct is only allocated for gods and the like, its first member is the vector of sphere ids
+        let spheres_addr = match fig_info_addr {
+            0 => 0,
+            a => read_mem::<usize>(&proc.handle, a + df.memory_layout.field_offset(OffsetSection::HistFigure, "spheres")),
+        };
+        let spheres = match spheres_addr {
+            0 => vec![],
+            a => mem_vec::<i16>(&proc.handle, a)
+                .iter()
+                .filter_map(|s| df.game_data.sphere_names.get(&s.to_string()).cloned())
+                .collect(),
+        };
+
+        let (name, translated_name) = df.read_names(proc, hf_addr + df.memory_layout.field_offset(OffsetSection::HistFigure, "hist_name"));
+        Some(Deity {
+            histfig_id,
+            name,
+            translated_name,
+            spheres,
+        })
+    }
+}
+
+/// A deity one dwarf worships and how much they need to pray to it
+#[derive(Default, Debug, Clone, Serialize, Deserialize)]
+pub struct Worship {
+    pub deity: Deity,
+    /// the strength of the deity link, 0 to 100
+    pub strength: i32,
+    /// how strongly they need to pray, 0 if they have no need for this deity
+    pub need_level: i32,
+    /// below zero when they've been kept from praying
+    pub focus_level: i32,
+    /// the prayers or services to this deity they're taking part in
+    pub prayers: Vec<String>,
+}
+
+impl Worship {
+    /// Every deity a dwarf is linked to, strongest first
+    pub unsafe fn for_dwarf(df: &DFInstance, proc: &Process, d: &Dwarf) -> Vec<Worship> {
+        let mut list: Vec<Worship> = d.histfig.links.iter()
+            .filter(|r| r.relation == RelationType::Deity)
+            .filter_map(|r| {
+                let deity = Deity::new(df, proc, r.histfig_id)?;
+                let need = d.needs.iter().find(|n| n.deity_id() == r.histfig_id);
+                Some(Worship {
+                    strength: r.strength,
+                    need_level: need.map_or(0, Need::need_level),
+                    focus_level: need.map_or(0, Need::focus_level),
+                    prayers: prayers_to(&d.activities, r.histfig_id),
+                    deity,
+                })
+            })
+            .collect();
+        list.sort_by_key(|w| Reverse(w.strength));
+        list
+    }
+
+    pub fn is_unmet(&self) -> bool {
+        self.need_level > 0 && self.focus_level < 0
+    }
+}
+
+/// The names of the prayer events to a deity
+fn prayers_to(activities: &[ActivityEvent], deity_id: i32) -> Vec<String> {
+    activities.iter()
+        .filter(|e| matches!(e.detail, ActivityDetail::Prayer { deity_id: id, .. } if id == deity_id))
+        .map(|e| e.name.clone())
+        .collect()
+}
+
+/// Who in the fortress worships a deity
+#[derive(Default, Debug, Clone, Serialize, Deserialize)]
+pub struct DeitySummary {
+    pub deity: Deity,
+    /// (dwarf id, name, strength)
+    pub worshippers: Vec<(i32, String, i32)>,
+    /// worshippers who have been kept from praying
+    pub unmet: Vec<(i32, String)>,
+    pub praying_now: Vec<(i32, String)>,
+    /// worshippers are being kept from praying, so a temple would help
+    pub needs_temple: bool,
+}
+
+/// Every deity worshipped in the fortress, most worshippers first
+pub fn fortress_deities(dwarves: &[Dwarf]) -> Vec<DeitySummary> {
+    let mut summary: Vec<DeitySummary> = vec![];
+    for d in dwarves {
+        for w in &d.worship {
+            let idx = match summary.iter().position(|s| s.deity.histfig_id == w.deity.histfig_id) {
+                Some(idx) => idx,
+                None => {
+                    summary.push(DeitySummary { deity: w.deity.clone(), ..Default::default() });
+                    summary.len() - 1
+                }
+            };
+            let s = &mut summary[idx];
+            s.worshippers.push((d.id, d.nice_name.clone(), w.strength));
+            if w.is_unmet() {
+                s.unmet.push((d.id, d.nice_name.clone()));
+                s.needs_temple = true;
+            }
+            if !w.prayers.is_empty() {
+                s.praying_now.push((d.id, d.nice_name.clone()));
+            }
+        }
+    }
+    summary.sort_by_key(|s| Reverse(s.worshippers.len()));
+    summary
+}
@@ -2,7 +2,7 @@
 use std::collections::HashMap;
 
 use serde::{Deserialize, Serialize};
-use crate::{dfinstance::DFInstance, win::{memory::memory::read_mem, process::Process}};
+use crate::{caste::caste::Caste, dfinstance::DFInstance, win::{memory::memory::read_mem, process::Process}};
 
 #[derive(Default, Debug, Clone, Serialize, Deserialize)]
     pub struct Skill {
@@ -19,10 +19,12 @@ use crate::{dfinstance::DFInstance, win::{memory::memory::read_mem, process::Pro
         level_capped: bool,
         rust: i32,
         rust_level: i32,
+        /// the caste's learning rate for this skill, 100 is normal
+        learn_rate: i32,
     }
 
     impl Skill {
-        pub unsafe fn new(df: &DFInstance, proc: &Process, addr: usize) -> Self {
+        pub unsafe fn new(df: &DFInstance, proc: &Process, caste: &Caste, addr: usize) -> Self {
             let mut skill = Skill {
                 id:             read_mem::<i16>(&proc.handle, addr) as i32,
                 raw_level:      read_mem::<i16>(&proc.handle, addr + 0x04) as i32,
@@ -32,6 +34,7 @@ use crate::{dfinstance::DFInstance, win::{memory::memory::read_mem, process::Pro
             };
 
             skill.name = df.game_data.skills.get(skill.id as usize).unwrap().name.clone();
+            skill.learn_rate = caste.skill_rate(skill.id);
 
             if skill.raw_level > 20 {
                     skill.level = 20;
@@ -73,6 +76,14 @@ use crate::{dfinstance::DFInstance, win::{memory::memory::read_mem, process::Pro
             skill
         }
 
+        pub fn id(&self) -> i32 {
+            self.id
+        }
+
+        pub fn raw_level(&self) -> i32 {
+            self.raw_level
+        }
+
         pub fn xp_for_level(level: i32) -> i32 {
             if level < 0 {
                 return 0;
@@ -1,20 +1,34 @@
 use std::collections::HashMap;
+use std::error::Error;
 use serde::{Deserialize, Serialize};
 
 use crate::DFInstance;
 use crate::data::memorylayout::OffsetSection;
-use crate::win::memory::memory::{mem_vec, read_mem};
+use crate::dwarf::dwarf::Dwarf;
+use crate::logger::logger_display_name;
+use crate::uniform::{EquipmentIssue, Uniform};
+use crate::util::global_address;
+use crate::util::memory::read_mem_as_string;
+use crate::win::memory::memory::{mem_vec, read_mem, write_mem};
 use crate::win::process::Process;
 
+/// Fortress positions that command a squad. Holders of these can only take the leader slot.
+const SQUAD_LEADER_POSITIONS: [&str; 4] = ["militia commander", "militia captain", "captain of the guard", "sheriff"];
+
 #[derive(Debug, Default, PartialEq, Clone, Serialize, Deserialize)]
 pub struct Squad {
     pub id: i32,
     pub addr: usize,
     pub name: String,
+    /// squad position -> historical figure id, -1 if the slot is empty
     pub members: HashMap<i32, i32>,
     pub orders: HashMap<i32, SquadOrderType>,
-    // TODO: uniforms
-    // pub uniforms: HashMap<i32, Uniform>,
+    /// squad position -> uniform
+    pub uniforms: HashMap<i32, Uniform>,
+    /// ammunition issued to each member
+    pub ammo_each: i32,
+    pub carry_food: bool,
+    pub carry_water: bool,
     pub inactive: bool,
 
     pub squad_order: SquadOrderType,
@@ -38,8 +52,8 @@ impl Squad {
     }
 
     pub unsafe fn read_name(&mut self, df: &DFInstance, proc: &Process) {
-        let name = read_mem::<String>(&proc.handle, self.addr + df.memory_layout.field_offset(OffsetSection::Squad, "name"));
-        let alias = read_mem::<String>(&proc.handle, self.addr + df.memory_layout.field_offset(OffsetSection::Squad, "alias"));
+        let name = df.read_translated_name(proc, self.addr + df.memory_layout.field_offset(OffsetSection::Squad, "name"));
+        let alias = read_mem_as_string(proc, self.addr + df.memory_layout.field_offset(OffsetSection::Squad, "alias"));
         if alias.is_empty() {
             self.name = name;
         } else {
@@ -51,55 +65,68 @@ impl Squad {
         let members_addr = self.addr + df.memory_layout.field_offset(OffsetSection::Squad, "members");
         let members_vector = mem_vec(&proc.handle, members_addr);
 
-        // not sure why not just members_vector.len()
+        // the vector always holds every slot, empty ones have an occupant of -1
         let mut member_count = 0;
-        for m in members_vector {
-            let addr = read_mem::<usize>(&proc.handle, m);
-            if addr != 0 {
+        for (pos, m) in members_vector.iter().enumerate() {
+            let histfig_id = read_mem::<i32>(&proc.handle, *m);
+            if histfig_id >= 0 {
                 member_count += 1;
             }
+            self.members.insert(pos as i32, histfig_id);
+            self.uniforms.insert(pos as i32, Uniform::new(df, proc, *m));
         }
 
-        let carry_food = read_mem::<i16>(&proc.handle, self.addr + df.memory_layout.field_offset(OffsetSection::Squad, "carry_food"));
-        let carry_water = read_mem::<i16>(&proc.handle, self.addr + df.memory_layout.field_offset(OffsetSection::Squad, "carry_water"));
+        self.carry_food = read_mem::<i16>(&proc.handle, self.addr + df.memory_layout.field_offset(OffsetSection::Squad, "carry_food")) != 0;
+        self.carry_water = read_mem::<i16>(&proc.handle, self.addr + df.memory_layout.field_offset(OffsetSection::Squad, "carry_water")) != 0;
 
         // add ammo qty of each member to ammo count
         let mut ammo_count = 0;
         for a in mem_vec::<usize>(&proc.handle, self.addr + df.memory_layout.field_offset(OffsetSection::Squad, "ammunition")) {
-             ammo_count += read_mem::<i32>(&proc.handle, self.addr + df.memory_layout.field_offset(OffsetSection::Squad, "ammunition_qty"));
+             ammo_count += read_mem::<i32>(&proc.handle, a + df.memory_layout.field_offset(OffsetSection::Squad, "ammunition_qty"));
         }
 
-        let mut ammo_each = 0;
         if member_count > 0 && ammo_count > 0 {
-            ammo_each = (ammo_count as f64 / member_count as f64).ceil() as i32;
+            self.ammo_each = (ammo_count as f64 / member_count as f64).ceil() as i32;
         }
-
-        // TODO: read uniforms lol
         }
 
         pub unsafe fn read_current_orders(&mut self, df: &DFInstance, proc: &Process) {
             let orders_addr = self.addr + df.memory_layout.field_offset(OffsetSection::Squad, "orders");
             let orders_vector = mem_vec(&proc.handle, orders_addr);
 
-            // current orders
+            // orders given to the whole squad
             for o in orders_vector {
-                let histfig_id = read_mem::<i32>(&proc.handle, o + df.memory_layout.field_offset(OffsetSection::Squad, "histfig_id"));
-                self.read_order(df, proc, o, histfig_id);
+                self.read_order(df, proc, o, -1);
+            }
+
+            // orders given to single members are kept with their squad position
+            let members_vector = mem_vec::<usize>(&proc.handle, self.addr + df.memory_layout.field_offset(OffsetSection::Squad, "members"));
+            for m in members_vector {
+                let histfig_id = read_mem::<i32>(&proc.handle, m);
+                if histfig_id < 0 {
+                    continue;
+                }
+                for o in mem_vec::<usize>(&proc.handle, m + df.memory_layout.field_offset(OffsetSection::Squad, "position_orders")) {
+                    self.read_order(df, proc, o, histfig_id);
+                }
             }
         }
 
         pub unsafe fn read_scheduled_orders(&mut self, df: &DFInstance, proc: &Process) {
-            let schedules = mem_vec(&proc.handle, self.addr + df.memory_layout.field_offset(OffsetSection::Squad, "schedule"));
+            let schedules = mem_vec(&proc.handle, self.addr + df.memory_layout.field_offset(OffsetSection::Squad, "schedules"));
             // no idea what alert is
             let idx = read_mem::<i32>(&proc.handle, self.addr + df.memory_layout.field_offset(OffsetSection::Squad, "alert"));
             let schedule_size = df.memory_layout.field_offset(OffsetSection::Squad, "sched_size");
             let current_month = df.current_time(proc).current_month();
 
-            let base_addr = schedules.get(idx as usize).unwrap();
+            let base_addr = match schedules.get(idx as usize) {
+                Some(addr) => addr,
+                None => return,
+            };
             let orders = mem_vec(&proc.handle, base_addr + df.memory_layout.field_offset(OffsetSection::Squad, "sched_orders"));
-            let assigned = mem_vec(&proc.handle, base_addr + df.memory_layout.field_offset(OffsetSection::Squad, "sched_assigned"));
+            let assigned = mem_vec(&proc.handle, base_addr + df.memory_layout.field_offset(OffsetSection::Squad, "sched_assign"));
 
-            let pos = 0;
+            let mut pos = 0;
             while pos < assigned.len() {
                 let addr = *assigned.get(pos).unwrap();
                 let order_id = read_mem::<i32>(&proc.handle, addr);
@@ -115,6 +142,7 @@ impl Squad {
                 } else {
                     self.orders.insert(*histfig_id, self.squad_order);
                 }
+                pos += 1;
             }
         }
 
@@ -142,6 +170,145 @@ impl Squad {
             }
         }
 
+        /// Returns the first empty slot in the squad
+        pub fn free_position(&self) -> Option<i32> {
+            let mut positions: Vec<&i32> = self.members.keys().collect();
+            positions.sort();
+            positions.into_iter().find(|&p| self.members[p] < 0).copied()
+        }
+
+        /// Checks that a dwarf may take the given squad slot, without writing anything
+        pub fn can_assign(&self, df: &DFInstance, dwarf: &Dwarf, position: i32) -> Result<(), Box<dyn Error>> {
+            let n = logger_display_name("Squad::can_assign");
+
+            match self.members.get(&position) {
+                Some(&histfig_id) if histfig_id < 0 || histfig_id == dwarf.histfig_id => (),
+                Some(_) => return Err(format!("{n} | Position {position} in {} is already taken", self.name).into()),
+                None => return Err(format!("{n} | {} has no position {position}", self.name).into()),
+            }
+
+            if dwarf.civ_id != df.dwarf_civ_id {
+                return Err(format!("{n} | {} is not a citizen", dwarf.first_name).into());
+            }
+            if !dwarf.is_adult() {
+                return Err(format!("{n} | {} is not an adult", dwarf.first_name).into());
+            }
+            if dwarf.histfig_id < 0 {
+                return Err(format!("{n} | {} has no historical figure", dwarf.first_name).into());
+            }
+
+            let noble = dwarf.noble_position.name.to_lowercase();
+            if !noble.is_empty() {
+                let leads_squad = SQUAD_LEADER_POSITIONS.contains(&noble.as_str());
+                if !leads_squad || position != 0 {
+                    return Err(format!("{n} | {} holds the position of {} and can't take position {position}", dwarf.first_name, dwarf.noble_position.name).into());
+                }
+            }
+            Ok(())
+        }
+
+        /// Moves a dwarf into the given squad slot by writing to the squad and the unit.
+        pub unsafe fn assign_member(&mut self, df: &DFInstance, proc: &Process, dwarf: &mut Dwarf, position: i32) -> Result<(), Box<dyn Error>> {
+            let n = logger_display_name("Squad::assign_member");
+            self.can_assign(df, dwarf, position)?;
+
+            // a dwarf can only be in one squad at a time
+            if dwarf.squad.addr != 0 && dwarf.squad.addr != self.addr {
+                return Err(format!("{n} | {} is already in {}", dwarf.first_name, dwarf.squad.name).into());
+            }
+
+            let old_position = self.members.iter().find(|(_, &h)| h == dwarf.histfig_id).map(|(&p, _)| p);
+            if old_position == Some(position) {
+                return Ok(());
+            }
+            let position_addr = self.position_address(df, proc, position)?;
+            let old_position_addr = match old_position {
+                Some(p) => Some((p, self.position_address(df, proc, p)?)),
+                None => None,
+            };
+
+            // moving within the squad leaves the old slot empty
+            if let Some((p, addr)) = old_position_addr {
+                write_mem::<i32>(&proc.handle, addr, -1);
+                self.members.insert(p, -1);
+            }
+            write_mem::<i32>(&proc.handle, position_addr, dwarf.histfig_id);
+            write_mem::<i32>(&proc.handle, dwarf.addr + df.memory_layout.field_offset(OffsetSection::Dwarf, "squad_id"), self.id);
+            write_mem::<i32>(&proc.handle, dwarf.addr + df.memory_layout.field_offset(OffsetSection::Dwarf, "squad_position"), position);
+            self.flag_equipment_update(df, proc);
+
+            self.members.insert(position, dwarf.histfig_id);
+            dwarf.squad = self.clone();
+            dwarf.squad_position = position;
+            dwarf.pending_squad_id = self.id;
+            dwarf.pending_squad_position = position;
+            dwarf.pending_squad_name = self.name.clone();
+            Ok(())
+        }
+
+        /// Removes a dwarf from this squad, clearing both the squad slot and the unit.
+        pub unsafe fn remove_member(&mut self, df: &DFInstance, proc: &Process, dwarf: &mut Dwarf) -> Result<(), Box<dyn Error>> {
+            let n = logger_display_name("Squad::remove_member");
+
+            let position = match self.members.iter().find(|(_, &h)| h == dwarf.histfig_id) {
+                Some((&p, _)) => p,
+                None => return Err(format!("{n} | {} is not a member of {}", dwarf.first_name, self.name).into()),
+            };
+
+            let position_addr = self.position_address(df, proc, position)?;
+            write_mem::<i32>(&proc.handle, position_addr, -1);
+            write_mem::<i32>(&proc.handle, dwarf.addr + df.memory_layout.field_offset(OffsetSection::Dwarf, "squad_id"), -1);
+            write_mem::<i32>(&proc.handle, dwarf.addr + df.memory_layout.field_offset(OffsetSection::Dwarf, "squad_position"), -1);
+            self.flag_equipment_update(df, proc);
+
+            self.members.insert(position, -1);
+            dwarf.squad = Squad::default();
+            dwarf.squad_position = -1;
+            dwarf.pending_squad_id = -1;
+            dwarf.pending_squad_position = -1;
+            dwarf.pending_squad_name = String::new();
+            Ok(())
+        }
+
+        unsafe fn position_address(&self, df: &DFInstance, proc: &Process, position: i32) -> Result<usize, Box<dyn Error>> {
+            let members_vector = mem_vec::<usize>(&proc.handle, self.addr + df.memory_layout.field_offset(OffsetSection::Squad, "members"));
+            match members_vector.get(position as usize) {
+                Some(&addr) if addr != 0 => Ok(addr),
+                _ => Err(format!("Squad position {position} not found in {}", self.name).into()),
+            }
+        }
+
+        /// Each member's equipment problems, soldiers with none are left out
+        pub fn equipment_report(&self, dwarves: &[Dwarf]) -> Vec<SoldierEquipment> {
+            dwarves.iter()
+                .filter(|d| d.squad.id == self.id && d.squad.addr != 0 && !d.equipment_issues.is_empty())
+                .map(|d| SoldierEquipment {
+                    dwarf_id: d.id,
+                    name: d.nice_name.clone(),
+                    squad_id: self.id,
+                    squad_name: self.name.clone(),
+                    position: d.squad_position,
+                    issues: d.equipment_issues.clone(),
+                })
+                .collect()
+        }
+
+        /// Tells the game to recheck uniforms after the members change
+        unsafe fn flag_equipment_update(&self, df: &DFInstance, proc: &Process) {
+            write_mem::<u8>(&proc.handle, self.addr + df.memory_layout.field_offset(OffsetSection::Squad, "equipment_update"), 1);
+            write_mem::<u8>(&proc.handle, global_address(proc, df.memory_layout.field_offset(OffsetSection::Addresses, "global_equipment_update")), 1);
+        }
+
+}
+
+#[derive(Debug, Default, Clone, Serialize, Deserialize)]
+pub struct SoldierEquipment {
+    pub dwarf_id: i32,
+    pub name: String,
+    pub squad_id: i32,
+    pub squad_name: String,
+    pub position: i32,
+    pub issues: Vec<EquipmentIssue>,
 }
 
 #[derive(Debug, Default, PartialEq, Copy, Clone, Serialize, Deserialize)]
@@ -0,0 +1,115 @@
+use std::collections::HashMap;
+
+use serde::{Deserialize, Serialize};
+
+use crate::items::{Item, ItemQuality, ItemType};
+
+/// How many of one kind of item the fortress has, e.g. every superior steel breastplate
+#[derive(Default, Debug, Clone, Serialize, Deserialize)]
+pub struct StockCount {
+    pub item_type: ItemType,
+    pub subtype_name: String,
+    pub material: String,
+    pub quality: ItemQuality,
+    pub count: i32,
+    pub forbidden: i32,
+    pub in_use: i32,
+}
+
+/// Filters for the stock list, any that are `None` match everything
+#[derive(Default, Debug, Clone, Deserialize)]
+pub struct StockQuery {
+    pub item_type: Option<ItemType>,
+    pub material: Option<String>,
+    pub min_quality: Option<ItemQuality>,
+}
+
+impl StockQuery {
+    pub fn matches(&self, item: &Item) -> bool {
+        self.item_type.is_none_or(|t| item.item_type == t) &&
+        self.material.as_ref().is_none_or(|m| item.material.eq_ignore_ascii_case(m)) &&
+        self.min_quality.is_none_or(|q| item.quality >= q)
+    }
+}
+
+/// Groups the fortress' items by type, subtype, material and quality
+pub fn stock_counts(items: &[Item], query: &StockQuery) -> Vec<StockCount> {
+    let mut groups: HashMap<(ItemType, &str, &str, ItemQuality), StockCount> = HashMap::new();
+    for item in items.iter().filter(|i| i.is_fortress_stock() && query.matches(i)) {
+        // stacks like ammo count every piece
+        let amount = item.stack_size.max(1);
+        let c = groups.entry((item.item_type, &item.subtype_name, &item.material, item.quality))
+            .or_insert_with(|| StockCount {
+                item_type: item.item_type,
+                subtype_name: item.subtype_name.clone(),
+                material: item.material.clone(),
+                quality: item.quality,
+                ..Default::default()
+            });
+        c.count += amount;
+        if item.is_forbidden() {
+            c.forbidden += amount;
+        }
+        if item.in_use() {
+            c.in_use += amount;
+        }
+    }
+
+    let mut counts: Vec<StockCount> = groups.into_values().collect();
+    counts.sort_by(|a, b| {
+        (a.item_type as i32).cmp(&(b.item_type as i32))
+            .then(a.subtype_name.cmp(&b.subtype_name))
+            .then(a.material.cmp(&b.material))
+            .then(b.quality.cmp(&a.quality))
+    });
+    counts
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::items::ItemFlag;
+
+    fn item(item_type: ItemType, material: &str, quality: ItemQuality, stack_size: i32, flags: &[ItemFlag]) -> Item {
+        let mut item = Item {
+            item_type,
+            material: material.to_string(),
+            quality,
+            stack_size,
+            ..Default::default()
+        };
+        flags.iter().for_each(|&f| item.set_flag(f));
+        item
+    }
+
+    #[test]
+    fn groups_matching_items() {
+        let items = vec![
+            item(ItemType::Ammo, "iron", ItemQuality::Ordinary, 25, &[]),
+            item(ItemType::Ammo, "iron", ItemQuality::Ordinary, 10, &[ItemFlag::Forbid]),
+            item(ItemType::Ammo, "iron", ItemQuality::Superior, 5, &[ItemFlag::InInventory]),
+            item(ItemType::Weapon, "steel", ItemQuality::Ordinary, 1, &[]),
+        ];
+        let counts = stock_counts(&items, &StockQuery::default());
+        assert_eq!(counts.len(), 3);
+
+        let ordinary = counts.iter().find(|c| c.item_type == ItemType::Ammo && c.quality == ItemQuality::Ordinary).unwrap();
+        assert_eq!((ordinary.count, ordinary.forbidden, ordinary.in_use), (35, 10, 0));
+        let superior = counts.iter().find(|c| c.item_type == ItemType::Ammo && c.quality == ItemQuality::Superior).unwrap();
+        assert_eq!((superior.count, superior.forbidden, superior.in_use), (5, 0, 5));
+    }
+
+    #[test]
+    fn skips_foreign_and_filtered_items() {
+        let items = vec![
+            item(ItemType::Weapon, "steel", ItemQuality::Masterful, 0, &[]),
+            item(ItemType::Weapon, "steel", ItemQuality::Masterful, 1, &[ItemFlag::Trader]),
+            item(ItemType::Weapon, "copper", ItemQuality::Ordinary, 1, &[]),
+        ];
+        let query = StockQuery { material: Some("STEEL".to_string()), ..Default::default() };
+        let counts = stock_counts(&items, &query);
+        assert_eq!(counts.len(), 1);
+        // an empty stack still counts as one item
+        assert_eq!(counts[0].count, 1);
+    }
+}
@@ -1,3 +1,5 @@
+use std::collections::HashMap;
+
 use serde::{Deserialize, Serialize};
 
 use crate::DFInstance;
@@ -14,6 +16,34 @@ pub struct Syndrome {
     pub has_transform: bool,
     pub transform_race: i32,
     pub class_names: Vec<String>,
+    /// attribute id -> change, from the attribute change effects
+    pub attribute_changes: HashMap<i32, AttributeChange>,
+    /// permanent syndromes have effects without an end
+    pub is_permanent: bool,
+}
+
+/// Creature interaction effect types we decode
+const EFFECT_TRANSFORMATION: i32 = 24;
+const EFFECT_PHYS_ATT_CHANGE: i32 = 25;
+const EFFECT_MENT_ATT_CHANGE: i32 = 26;
+
+/// A syndrome's change to one attribute, the new value is `value * percent / 100 + add`
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+pub struct AttributeChange {
+    pub percent: i32,
+    pub add: i32,
+}
+
+impl Default for AttributeChange {
+    fn default() -> Self {
+        AttributeChange { percent: 100, add: 0 }
+    }
+}
+
+impl AttributeChange {
+    pub fn apply(&self, value: i32) -> i32 {
+        (value * self.percent / 100 + self.add).max(0)
+    }
 }
 
 impl Syndrome {
@@ -26,6 +56,7 @@ impl Syndrome {
             id,
             name: read_mem_as_string(&proc, addr),
             is_sickness: read_mem::<u8>(&proc.handle, addr + df.memory_layout.field_offset(OffsetSection::Dwarf, "syn_sick_flag")),
+            transform_race: -1,
             ..Default::default()
         };
 
@@ -42,25 +73,42 @@ impl Syndrome {
             let vtable = read_mem::<usize>(&proc.handle, vtable_addr);
             let effect_type = read_mem::<i32>(&proc.handle, vtable + 0x1);
             let end = read_mem::<i32>(&proc.handle, e + df.memory_layout.field_offset(OffsetSection::Syndrome, "cie_end"));
+            if end < 0 {
+                s.is_permanent = true;
+            }
 
             match effect_type {
-                25 =>  {
-                    // TODO: attribute change
-                },
-                26 => {
-                    // TODO: attribute change
-                },
-                24 => {
-                    // TODO: transformation
+                // physical attributes come first, then the mental ones
+                EFFECT_PHYS_ATT_CHANGE => s.read_attribute_changes(df, proc, e, 0, 6, "cie_phys"),
+                EFFECT_MENT_ATT_CHANGE => s.read_attribute_changes(df, proc, e, 6, 13, "cie_ment"),
+                EFFECT_TRANSFORMATION => {
+                    let races = mem_vec::<i32>(&proc.handle, e + df.memory_layout.field_offset(OffsetSection::Syndrome, "trans_race_vec"));
+                    if let Some(&race) = races.first() {
+                        s.has_transform = true;
+                        s.transform_race = race;
+                    }
                 },
-                _ => {
-                    s.name = "???".to_string();
-                }
+                _ => (),
             }
         }
         s
     }
 
+    /// Percentages start at `cie_first_perc`, the flat additions at `add_offset`, one i32 per attribute
+    unsafe fn read_attribute_changes(&mut self, df: &DFInstance, proc: &Process, effect: usize, first_attr: i32, count: usize, add_offset: &str) {
+        let perc_addr = effect + df.memory_layout.field_offset(OffsetSection::Syndrome, "cie_first_perc");
+        let add_addr = effect + df.memory_layout.field_offset(OffsetSection::Syndrome, add_offset);
+        for i in 0..count {
+            let change = AttributeChange {
+                percent: read_mem::<i32>(&proc.handle, perc_addr + i * 4),
+                add: read_mem::<i32>(&proc.handle, add_addr + i * 4),
+            };
+            if change.percent != 100 || change.add != 0 {
+                self.attribute_changes.insert(first_attr + i as i32, change);
+            }
+        }
+    }
+
     pub unsafe fn display_name(self) -> String {
         let mut name = "???".to_string();
 
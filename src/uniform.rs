@@ -0,0 +1,293 @@
+use serde::{Deserialize, Serialize};
+
+use crate::DFInstance;
+use crate::data::memorylayout::OffsetSection;
+use crate::inventory::InventoryItem;
+use crate::items::material::{Material, MaterialFlag};
+use crate::items::{Item, ItemType};
+use crate::util::memory::read_mem_as_string;
+use crate::win::memory::memory::{mem_vec, read_mem};
+use crate::win::process::Process;
+
+/// Items at this wear level or above (threadbare, tattered) should be replaced
+const WORN_OUT_WEAR: i16 = 2;
+
+/// The individual choice bit for "any ranged weapon"
+const CHOICE_RANGED: i32 = 1 << 2;
+
+/// The material categories a uniform can ask for instead of a specific material
+#[derive(Debug, Default, PartialEq, Eq, Copy, Clone, Serialize, Deserialize)]
+pub enum UniformMaterialClass {
+    #[default]
+    None = -1,
+    Leather = 1,
+    Cloth = 2,
+    Wood = 3,
+    Stone = 5,
+    Metal = 14,
+    Gem = 17,
+    Bone = 18,
+    Shell = 19,
+    Pearl = 20,
+    Tooth = 21,
+    Horn = 22,
+    PlantFiber = 27,
+    Silk = 28,
+    Yarn = 29,
+}
+
+impl From<i16> for UniformMaterialClass {
+    fn from(value: i16) -> Self {
+        match value {
+            1 => UniformMaterialClass::Leather,
+            2 => UniformMaterialClass::Cloth,
+            3 => UniformMaterialClass::Wood,
+            5 => UniformMaterialClass::Stone,
+            14 => UniformMaterialClass::Metal,
+            17 => UniformMaterialClass::Gem,
+            18 => UniformMaterialClass::Bone,
+            19 => UniformMaterialClass::Shell,
+            20 => UniformMaterialClass::Pearl,
+            21 => UniformMaterialClass::Tooth,
+            22 => UniformMaterialClass::Horn,
+            27 => UniformMaterialClass::PlantFiber,
+            28 => UniformMaterialClass::Silk,
+            29 => UniformMaterialClass::Yarn,
+            _ => UniformMaterialClass::None,
+        }
+    }
+}
+
+impl UniformMaterialClass {
+    pub fn matches(&self, mat: &Material) -> bool {
+        match self {
+            UniformMaterialClass::None => true,
+            UniformMaterialClass::Leather => mat.has_flag(MaterialFlag::Leather),
+            UniformMaterialClass::Cloth => mat.has_flag(MaterialFlag::ThreadPlant) || mat.has_flag(MaterialFlag::Silk) || mat.has_flag(MaterialFlag::Yarn),
+            UniformMaterialClass::Wood => mat.has_flag(MaterialFlag::IsWood),
+            UniformMaterialClass::Stone => mat.has_flag(MaterialFlag::IsStone),
+            UniformMaterialClass::Metal => mat.has_flag(MaterialFlag::IsMetal),
+            UniformMaterialClass::Gem => mat.has_flag(MaterialFlag::IsGem),
+            UniformMaterialClass::Bone => mat.has_flag(MaterialFlag::Bone),
+            UniformMaterialClass::Shell => mat.has_flag(MaterialFlag::Shell),
+            UniformMaterialClass::Pearl => mat.has_flag(MaterialFlag::Pearl),
+            UniformMaterialClass::Tooth => mat.has_flag(MaterialFlag::Tooth),
+            UniformMaterialClass::Horn => mat.has_flag(MaterialFlag::Horn),
+            UniformMaterialClass::PlantFiber => mat.has_flag(MaterialFlag::ThreadPlant),
+            UniformMaterialClass::Silk => mat.has_flag(MaterialFlag::Silk),
+            UniformMaterialClass::Yarn => mat.has_flag(MaterialFlag::Yarn),
+        }
+    }
+}
+
+/// One piece of a squad position's uniform
+#[derive(Debug, Default, PartialEq, Clone, Serialize, Deserialize)]
+pub struct UniformItem {
+    /// a specific item picked for this slot, -1 if any matching item will do
+    pub item_id: i32,
+    pub item_type: ItemType,
+    pub subtype: i16,
+    pub subtype_name: String,
+    pub mat_class: UniformMaterialClass,
+    pub mat_type: i16,
+    pub mat_index: i32,
+    pub material: String,
+    /// "individual choice" items like "any melee weapon"
+    pub individual_choice: bool,
+    /// a crossbow, bow or other weapon that fires ammo
+    pub ranged: bool,
+}
+
+impl UniformItem {
+    pub unsafe fn new(df: &DFInstance, proc: &Process, addr: usize, default_type: ItemType) -> UniformItem {
+        let item_type = read_mem::<i16>(&proc.handle, addr + df.memory_layout.field_offset(OffsetSection::Squad, "uniform_spec_item_type"));
+        let choice = read_mem::<i32>(&proc.handle, addr + df.memory_layout.field_offset(OffsetSection::Squad, "uniform_indiv_choice"));
+        let mut u = UniformItem {
+            item_id:    read_mem::<i32>(&proc.handle, addr),
+            item_type:  if item_type < 0 { default_type } else { ItemType::from_i32(item_type as i32) },
+            subtype:    read_mem::<i16>(&proc.handle, addr + df.memory_layout.field_offset(OffsetSection::Squad, "uniform_spec_item_subtype")),
+            mat_class:  UniformMaterialClass::from(read_mem::<i16>(&proc.handle, addr + df.memory_layout.field_offset(OffsetSection::Squad, "uniform_spec_mat_class"))),
+            mat_type:   read_mem::<i16>(&proc.handle, addr + df.memory_layout.field_offset(OffsetSection::Squad, "uniform_spec_mat_type")),
+            mat_index:  read_mem::<i32>(&proc.handle, addr + df.memory_layout.field_offset(OffsetSection::Squad, "uniform_spec_mat_index")),
+            individual_choice: choice != 0,
+            ranged:     choice & CHOICE_RANGED != 0,
+            ..Default::default()
+        };
+
+        if let Some(&def) = df.item_defs.get(&u.item_type).and_then(|defs| defs.get(u.subtype as usize)).filter(|_| u.subtype >= 0) {
+            u.subtype_name = read_mem_as_string(proc, def + df.memory_layout.field_offset(OffsetSection::ItemSubtype, "name"));
+            // ranged weapons name the class of ammo they fire
+            if u.item_type == ItemType::Weapon {
+                u.ranged |= !read_mem_as_string(proc, def + df.memory_layout.field_offset(OffsetSection::WeaponSubtype, "ammo")).is_empty();
+            }
+        }
+        if u.mat_type >= 0 {
+            if let Some(mat) = df.get_material(proc, u.mat_index, u.mat_type) {
+                u.material = mat.name(Default::default());
+            }
+        }
+        u
+    }
+
+    /// e.g. "steel breastplate" or "leather armor"
+    pub fn description(&self) -> String {
+        let base = match self.subtype_name.is_empty() {
+            true => self.item_type.to_string(),
+            false => self.subtype_name.clone(),
+        };
+        let mat = match (self.material.is_empty(), self.mat_class) {
+            (false, _) => self.material.clone(),
+            (true, UniformMaterialClass::None) => String::new(),
+            (true, class) => format!("{:?}", class).to_lowercase(),
+        };
+        match mat.is_empty() {
+            true => base,
+            false => format!("{mat} {base}"),
+        }
+    }
+
+    fn fits(&self, item: &Item) -> bool {
+        item.item_type == self.item_type && (self.subtype < 0 || item.subtype == self.subtype)
+    }
+
+    unsafe fn material_matches(&self, df: &DFInstance, proc: &Process, item: &Item) -> bool {
+        if self.mat_type >= 0 {
+            return item.mat_type == self.mat_type && item.mat_index == self.mat_index;
+        }
+        match df.get_material(proc, item.mat_index, item.mat_type) {
+            Some(mat) => self.mat_class.matches(mat),
+            None => self.mat_class == UniformMaterialClass::None,
+        }
+    }
+}
+
+/// What a squad position is supposed to be wearing and carrying
+#[derive(Debug, Default, PartialEq, Clone, Serialize, Deserialize)]
+pub struct Uniform {
+    pub items: Vec<UniformItem>,
+    /// ids of the containers assigned to the position, -1 if none
+    pub quiver_id: i32,
+    pub backpack_id: i32,
+    pub flask_id: i32,
+}
+
+impl Uniform {
+    /// Reads the uniform of the squad position at `addr`
+    pub unsafe fn new(df: &DFInstance, proc: &Process, addr: usize) -> Uniform {
+        // field offset name, default item type
+        let categories = [
+            ("armor_vector", ItemType::Armor),
+            ("helm_vector", ItemType::Helm),
+            ("pants_vector", ItemType::Pants),
+            ("gloves_vector", ItemType::Gloves),
+            ("shoes_vector", ItemType::Shoes),
+            ("shield_vector", ItemType::Shield),
+            ("weapon_vector", ItemType::Weapon),
+        ];
+
+        let mut u = Uniform {
+            quiver_id:   read_mem::<i32>(&proc.handle, addr + df.memory_layout.field_offset(OffsetSection::Squad, "quiver")),
+            backpack_id: read_mem::<i32>(&proc.handle, addr + df.memory_layout.field_offset(OffsetSection::Squad, "backpack")),
+            flask_id:    read_mem::<i32>(&proc.handle, addr + df.memory_layout.field_offset(OffsetSection::Squad, "flask")),
+            ..Default::default()
+        };
+        for (offset_name, item_type) in categories {
+            for spec in mem_vec::<usize>(&proc.handle, addr + df.memory_layout.field_offset(OffsetSection::Squad, offset_name)) {
+                u.items.push(UniformItem::new(df, proc, spec, item_type));
+            }
+        }
+        u
+    }
+
+    /// Whether the position fires ammo and so needs a quiver and bolts
+    pub fn is_ranged(&self) -> bool {
+        self.quiver_id >= 0 || self.items.iter().any(|i| i.ranged)
+    }
+
+    /// Compares the uniform with what a soldier is actually carrying
+    pub unsafe fn check(&self, df: &DFInstance, proc: &Process, inventory: &[InventoryItem], ammo_each: i32, carry_food: bool, carry_water: bool) -> Vec<EquipmentIssue> {
+        let mut issues = vec![];
+        let mut used: Vec<usize> = vec![];
+        let equipped: Vec<(usize, &Item)> = inventory.iter()
+            .enumerate()
+            .filter(|(_, i)| i.is_equipped())
+            .map(|(idx, i)| (idx, &i.item))
+            .collect();
+
+        for spec in &self.items {
+            let candidates: Vec<(usize, &Item)> = equipped.iter()
+                .filter(|(idx, item)| !used.contains(idx) && spec.fits(item))
+                .copied()
+                .collect();
+
+            // prefer the assigned item, then one of the right material
+            let found = candidates.iter().find(|(_, item)| spec.item_id >= 0 && item.id == spec.item_id)
+                .or_else(|| candidates.iter().find(|(_, item)| spec.material_matches(df, proc, item)))
+                .or_else(|| candidates.first());
+
+            let (idx, item) = match found {
+                Some(&f) => f,
+                None => {
+                    issues.push(EquipmentIssue::new(EquipmentProblem::Missing, spec.description(), String::new()));
+                    continue;
+                }
+            };
+            used.push(idx);
+
+            if !spec.material_matches(df, proc, item) {
+                issues.push(EquipmentIssue::new(EquipmentProblem::WrongMaterial, spec.description(), item.display_name()));
+            }
+            if item.wear >= WORN_OUT_WEAR {
+                issues.push(EquipmentIssue::new(EquipmentProblem::WornOut, spec.description(), item.display_name()));
+            }
+        }
+
+        let carries = |item_type: ItemType| inventory.iter().any(|i| i.item.item_type == item_type);
+        let containers = [
+            (self.quiver_id >= 0 || (self.is_ranged() && ammo_each > 0), ItemType::Quiver),
+            (self.backpack_id >= 0 || carry_food, ItemType::Backpack),
+            (self.flask_id >= 0 || carry_water, ItemType::Flask),
+        ];
+        for (needed, item_type) in containers {
+            if needed && !carries(item_type) {
+                issues.push(EquipmentIssue::new(EquipmentProblem::Missing, item_type.to_string(), String::new()));
+            }
+        }
+
+        if self.is_ranged() && ammo_each > 0 {
+            let carried: i32 = inventory.iter()
+                .flat_map(|i| std::iter::once(&i.item).chain(i.contained.iter()))
+                .filter(|i| i.item_type == ItemType::Ammo)
+                .map(|i| i.stack_size)
+                .sum();
+            if carried < ammo_each {
+                issues.push(EquipmentIssue::new(EquipmentProblem::AmmoShortfall, format!("{ammo_each} ammo"), format!("{carried} ammo")));
+            }
+        }
+        issues
+    }
+}
+
+#[derive(Debug, Default, PartialEq, Copy, Clone, Serialize, Deserialize)]
+pub enum EquipmentProblem {
+    #[default]
+    Missing,
+    WrongMaterial,
+    WornOut,
+    AmmoShortfall,
+}
+
+#[derive(Debug, Default, PartialEq, Clone, Serialize, Deserialize)]
+pub struct EquipmentIssue {
+    pub problem: EquipmentProblem,
+    /// what the uniform asks for
+    pub expected: String,
+    /// what the soldier has instead, empty if nothing
+    pub found: String,
+}
+
+impl EquipmentIssue {
+    fn new(problem: EquipmentProblem, expected: String, found: String) -> EquipmentIssue {
+        EquipmentIssue { problem, expected, found }
+    }
+}
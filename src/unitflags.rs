@@ -0,0 +1,114 @@
+use serde::{Deserialize, Serialize};
+
+/// Bits of the first unit flags word
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum UnitFlag1 {
+    MoveState = 0,
+    Dead = 1,
+    HasMood = 2,
+    HadMood = 3,
+    Marauder = 4,
+    Drowning = 5,
+    Merchant = 6,
+    Forest = 7,
+    Left = 8,
+    Rider = 9,
+    Incoming = 10,
+    Diplomat = 11,
+    Zombie = 12,
+    Skeleton = 13,
+    CanSwap = 14,
+    OnGround = 15,
+    Projectile = 16,
+    ActiveInvader = 17,
+    HiddenInAmbush = 18,
+    InvaderOrigin = 19,
+    Coward = 20,
+    HiddenAmbusher = 21,
+    Invades = 22,
+    CheckFlows = 23,
+    Ridden = 24,
+    Caged = 25,
+    Tame = 26,
+    Chained = 27,
+    RoyalGuard = 28,
+    FortressGuard = 29,
+    SuppressWield = 30,
+    ImportantHistoricalFigure = 31,
+}
+
+/// Bits of the second unit flags word
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum UnitFlag2 {
+    Swimming = 0,
+    Sparring = 1,
+    NoNotify = 2,
+    CalculatedNerves = 4,
+    CalculatedBodyParts = 5,
+    ImportantHistoricalFigure = 6,
+    Killed = 7,
+    Cleanup1 = 8,
+    Cleanup2 = 9,
+    Cleanup3 = 10,
+    ForTrade = 11,
+    TradeResolved = 12,
+    HasBreaks = 13,
+    Gutted = 14,
+    CirculatorySpray = 15,
+    LockedInForTrading = 16,
+    Slaughter = 17,
+    Underworld = 18,
+    Resident = 19,
+    Cleanup4 = 20,
+    CalculatedInsulation = 21,
+    VisitorUninvited = 22,
+    Visitor = 23,
+    CalculatedInventory = 24,
+    VisionGood = 25,
+    VisionDamaged = 26,
+    VisionMissing = 27,
+    BreathingGood = 28,
+    BreathingProblem = 29,
+    RoamingWildernessPopulationSource = 30,
+    RoamingWildernessPopulationSourceNotAMapFeature = 31,
+}
+
+/// Bits of the third unit flags word
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum UnitFlag3 {
+    BodyPartRelsizeComputed = 0,
+    SizeModifierComputed = 1,
+    StuckWeaponComputed = 2,
+    ComputeHealth = 3,
+    AnnounceTitan = 4,
+    OnCrutch = 6,
+    WeightComputed = 7,
+    BodyTempInRange = 8,
+    WaitUntilReveal = 9,
+    Scuttle = 10,
+    Ghostly = 12,
+    NoMeandering = 15,
+    Floundering = 16,
+    ExitVehicle1 = 17,
+    ExitVehicle2 = 18,
+    DangerousTerrain = 19,
+    AdvYield = 20,
+    VisionConeSet = 21,
+    EmotionallyOverloaded = 23,
+    AvailableForAdoption = 25,
+    Gelded = 26,
+    MarkedForGelding = 27,
+    InjuryThought = 28,
+}
+
+/// The flags worth showing as a unit's statuses
+pub const STATUS_FLAGS1: [UnitFlag1; 14] = [
+    UnitFlag1::Dead, UnitFlag1::Marauder, UnitFlag1::Merchant, UnitFlag1::Left, UnitFlag1::Diplomat,
+    UnitFlag1::Zombie, UnitFlag1::Skeleton, UnitFlag1::OnGround, UnitFlag1::ActiveInvader,
+    UnitFlag1::HiddenInAmbush, UnitFlag1::Caged, UnitFlag1::Tame, UnitFlag1::Chained, UnitFlag1::Drowning,
+];
+pub const STATUS_FLAGS2: [UnitFlag2; 7] = [
+    UnitFlag2::Killed, UnitFlag2::ForTrade, UnitFlag2::Slaughter, UnitFlag2::Underworld,
+    UnitFlag2::Resident, UnitFlag2::VisitorUninvited, UnitFlag2::Visitor,
+];
+pub const STATUS_FLAGS3: [UnitFlag3; 3] = [UnitFlag3::Ghostly, UnitFlag3::AvailableForAdoption, UnitFlag3::Gelded];